@@ -2,9 +2,18 @@
 //! specifically related to reconnect behavior.
 
 use crate::strategies::ExpBackoffStrategy;
+use crate::tokio::UnderlyingIo;
+use log::error;
+use std::future::Future;
 use std::io;
-use std::sync::Arc;
-use std::time::Duration;
+use std::net::SocketAddr;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
 /// Boxed iterator yielding the wait durations between reconnection attempts.
 ///
@@ -12,15 +21,18 @@ use std::time::Duration;
 pub type DurationIterator = Box<dyn Iterator<Item = Duration> + Send>;
 
 /// Events emitted by [`StubbornIo`](crate::tokio::StubbornIo) over the lifetime of
-/// a connection. Delivered to the single observer installed via
-/// [`ReconnectOptions::with_event_callback`].
+/// a connection.
+///
+/// Delivered to the observer installed via
+/// [`ReconnectOptions::with_event_callback`] and to every listener added with
+/// [`ReconnectOptions::add_event_listener`].
 ///
 /// Borrowed payloads (e.g. error references) are scoped to the callback invocation;
 /// implementations that need to retain data must clone it.
 ///
 /// Non-exhaustive so new variants can be added without breaking existing matches.
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ReconnectEvent<'a> {
     /// A (re)connection just completed successfully.
     ///
@@ -30,8 +42,32 @@ pub enum ReconnectEvent<'a> {
         /// 0 = initial connect; >= 1 = (re)connect attempt count.
         attempt: usize,
     },
+    /// Emitted immediately before [`Self::Connected`] by transports that choose
+    /// the remote address themselves (see
+    /// [`UnderlyingIo::resolved_addr`](crate::tokio::UnderlyingIo::resolved_addr)),
+    /// e.g. [`StubbornTcpHostStream`](crate::StubbornTcpHostStream) after
+    /// re-resolving its host name.
+    AddressResolved {
+        /// Same counter as [`Self::Connected::attempt`].
+        attempt: usize,
+        /// The resolved address that accepted the connection.
+        addr: SocketAddr,
+    },
     /// An established connection was lost; the reconnect machinery is engaging.
-    Disconnected,
+    Disconnected {
+        /// What ended the connection.
+        reason: DisconnectReason,
+    },
+    /// No bytes arrived within the configured
+    /// [`ReconnectOptions::with_read_idle_timeout`] window while a read was
+    /// pending, so the connection is presumed dead. Followed by
+    /// [`Self::Disconnected`].
+    IdleTimeout,
+    /// The context handed to `UnderlyingIo::establish` was replaced via
+    /// [`StubbornIo::set_context`](crate::tokio::StubbornIo::set_context) or
+    /// [`StubbornIo::retarget`](crate::tokio::StubbornIo::retarget); later
+    /// attempts connect to the new target.
+    ContextChanged,
     /// A connect or reconnect attempt failed. `attempt` is the same counter as
     /// [`Self::Connected::attempt`].
     ConnectFailed {
@@ -55,15 +91,278 @@ pub enum ReconnectEvent<'a> {
         /// Number of bytes the caller asked to write and the crate dropped.
         bytes_dropped: usize,
     },
-    /// The retries iterator has been exhausted and the stream has entered the
-    /// terminal `FailedAndExhausted` state. No further events will be emitted.
-    Exhausted,
+    /// Under [`WriteFailurePolicy::Replay`], the retained bytes have been
+    /// re-sent in full on the new connection. Follows [`Self::Connected`]; not
+    /// emitted when there was nothing to replay.
+    Replayed {
+        /// Number of bytes re-sent.
+        bytes: usize,
+    },
+    /// The stream gave up reconnecting and has entered the terminal
+    /// `FailedAndExhausted` state. No further events will be emitted.
+    Exhausted {
+        /// Why it gave up.
+        reason: ExhaustedReason,
+    },
+}
+
+/// Why a stream gave up reconnecting. Carried by [`ReconnectEvent::Exhausted`].
+///
+/// Non-exhaustive so new causes can be added without breaking existing matches.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExhaustedReason {
+    /// The retries iterator ran out.
+    RetriesExhausted,
+    /// An attempt failed with an error that
+    /// [`UnderlyingIo::is_fatal_connect_error`] classifies as fatal.
+    FatalError,
+    /// The initial connect failed under
+    /// [`ReconnectOptions::with_exit_if_first_connect_fails`].
+    FirstConnectFailed,
+    /// The [`RetryPolicy`] returned [`RetryDecision::GiveUp`].
+    RetryPolicy,
+    /// The stream stayed disconnected longer than
+    /// [`ReconnectOptions::with_max_disconnected_duration`] allows.
+    MaxDisconnectedDuration,
+}
+
+/// Why an established connection was given up on. Carried by
+/// [`ReconnectEvent::Disconnected`].
+///
+/// Non-exhaustive so new causes can be added without breaking existing matches.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// A read, write or flush failed with an error the transport classifies as
+    /// a disconnect (see [`UnderlyingIo::is_disconnect_error`]).
+    IoError(io::ErrorKind),
+    /// A read reported the end of the stream (see [`UnderlyingIo::is_final_read`]).
+    EndOfStream,
+    /// The [`ReconnectOptions::with_read_idle_timeout`] window elapsed.
+    IdleTimeout,
+    /// The caller asked for a new connection via
+    /// [`StubbornIo::force_reconnect`](crate::tokio::StubbornIo::force_reconnect).
+    Forced,
+    /// The connection context was replaced via
+    /// [`StubbornIo::retarget`](crate::tokio::StubbornIo::retarget).
+    ContextChanged,
+}
+
+/// An owned copy of a [`ReconnectEvent`].
+///
+/// Delivered by the stream returned from
+/// [`StubbornIo::take_event_stream`](crate::tokio::StubbornIo::take_event_stream)
+/// when [`ReconnectOptions::with_event_channel`] is set.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct OwnedReconnectEvent {
+    /// Wall-clock time at which the event was emitted.
+    pub timestamp: SystemTime,
+    /// The name set with [`ReconnectOptions::with_connection_name`].
+    pub connection_name: Arc<str>,
+    /// What happened.
+    pub kind: OwnedEventKind,
+}
+
+impl OwnedReconnectEvent {
+    pub(crate) fn new(connection_name: &Arc<str>, event: &ReconnectEvent<'_>) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            connection_name: Arc::clone(connection_name),
+            kind: OwnedEventKind::from(event),
+        }
+    }
 }
 
-/// Receiver for [`ReconnectEvent`]s. Stored as an `Arc<dyn Fn>` on
-/// [`ReconnectOptions`] so it can be cheaply cloned into reconnect futures.
+/// The variants of [`ReconnectEvent`], with owned payloads. See there for
+/// when each one is emitted.
+///
+/// Non-exhaustive so new variants can be added without breaking existing matches.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum OwnedEventKind {
+    /// See [`ReconnectEvent::Connected`].
+    Connected {
+        /// 0 = initial connect; >= 1 = (re)connect attempt count.
+        attempt: usize,
+    },
+    /// See [`ReconnectEvent::AddressResolved`].
+    AddressResolved {
+        /// Same counter as [`Self::Connected::attempt`].
+        attempt: usize,
+        /// The resolved address that accepted the connection.
+        addr: SocketAddr,
+    },
+    /// See [`ReconnectEvent::Disconnected`].
+    Disconnected {
+        /// What ended the connection.
+        reason: DisconnectReason,
+    },
+    /// See [`ReconnectEvent::IdleTimeout`].
+    IdleTimeout,
+    /// See [`ReconnectEvent::ContextChanged`].
+    ContextChanged,
+    /// See [`ReconnectEvent::ConnectFailed`].
+    ConnectFailed {
+        /// A copy of the error, with the same kind and message. The original
+        /// is returned to the reconnect machinery, so its source chain is not
+        /// preserved.
+        error: Arc<io::Error>,
+        /// Which attempt failed (0 = initial).
+        attempt: usize,
+    },
+    /// See [`ReconnectEvent::ReconnectScheduled`].
+    ReconnectScheduled {
+        /// Which attempt is being scheduled.
+        attempt: usize,
+        /// How long the machinery will sleep before invoking `establish` again.
+        delay: Duration,
+    },
+    /// See [`ReconnectEvent::WriteWhileDisconnected`].
+    WriteWhileDisconnected {
+        /// Number of bytes the caller asked to write and the crate dropped.
+        bytes_dropped: usize,
+    },
+    /// See [`ReconnectEvent::Replayed`].
+    Replayed {
+        /// Number of bytes re-sent.
+        bytes: usize,
+    },
+    /// See [`ReconnectEvent::Exhausted`].
+    Exhausted {
+        /// Why it gave up.
+        reason: ExhaustedReason,
+    },
+}
+
+impl From<&ReconnectEvent<'_>> for OwnedEventKind {
+    fn from(event: &ReconnectEvent<'_>) -> Self {
+        match *event {
+            ReconnectEvent::Connected { attempt } => Self::Connected { attempt },
+            ReconnectEvent::AddressResolved { attempt, addr } => {
+                Self::AddressResolved { attempt, addr }
+            }
+            ReconnectEvent::Disconnected { reason } => Self::Disconnected { reason },
+            ReconnectEvent::IdleTimeout => Self::IdleTimeout,
+            ReconnectEvent::ContextChanged => Self::ContextChanged,
+            ReconnectEvent::ConnectFailed { error, attempt } => Self::ConnectFailed {
+                error: Arc::new(io::Error::new(error.kind(), error.to_string())),
+                attempt,
+            },
+            ReconnectEvent::ReconnectScheduled { attempt, delay } => {
+                Self::ReconnectScheduled { attempt, delay }
+            }
+            ReconnectEvent::WriteWhileDisconnected { bytes_dropped } => {
+                Self::WriteWhileDisconnected { bytes_dropped }
+            }
+            ReconnectEvent::Replayed { bytes } => Self::Replayed { bytes },
+            ReconnectEvent::Exhausted { reason } => Self::Exhausted { reason },
+        }
+    }
+}
+
+/// Receiver for [`ReconnectEvent`]s. Stored as an `Arc<dyn Fn>` so the
+/// listener list can be snapshotted cheaply for each event.
 pub type EventCallback = Arc<dyn for<'a> Fn(ReconnectEvent<'a>) + Send + Sync>;
 
+/// Listener id reserved for the [`ReconnectOptions::with_event_callback`] slot.
+const CALLBACK_ID: u64 = 0;
+
+/// The observers of one stream, shared by its options, the stream itself and
+/// its reconnect sequences.
+pub(crate) struct EventListeners {
+    /// In delivery order: the callback slot first, if set, then listeners in
+    /// registration order.
+    listeners: Mutex<Vec<(u64, EventCallback)>>,
+    next_id: AtomicU64,
+}
+
+impl EventListeners {
+    fn new() -> Self {
+        Self {
+            listeners: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(CALLBACK_ID + 1),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(u64, EventCallback)>> {
+        self.listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_callback(&self, callback: EventCallback) {
+        let mut listeners = self.lock();
+        listeners.retain(|(id, _)| *id != CALLBACK_ID);
+        listeners.insert(0, (CALLBACK_ID, callback));
+    }
+
+    pub(crate) fn add(self: &Arc<Self>, listener: EventCallback) -> EventListenerHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().push((id, listener));
+        EventListenerHandle {
+            listeners: Arc::downgrade(self),
+            id,
+        }
+    }
+
+    /// Delivers `event` to every listener. A listener that panics is logged
+    /// and skipped; the rest still receive the event.
+    pub(crate) fn emit(&self, event: ReconnectEvent<'_>) {
+        // Snapshot, so listeners may add or unregister listeners themselves.
+        let listeners: Vec<EventCallback> =
+            self.lock().iter().map(|(_, cb)| Arc::clone(cb)).collect();
+        for listener in listeners {
+            if catch_unwind(AssertUnwindSafe(|| listener(event))).is_err() {
+                error!("Event listener panicked while handling {event:?}");
+            }
+        }
+    }
+}
+
+/// Returned by [`ReconnectOptions::add_event_listener`] and
+/// [`StubbornIo::add_event_listener`](crate::tokio::StubbornIo::add_event_listener).
+///
+/// Dropping the handle keeps the listener registered; call
+/// [`Self::unregister`] to remove it.
+#[derive(Debug)]
+pub struct EventListenerHandle {
+    listeners: Weak<EventListeners>,
+    id: u64,
+}
+
+impl EventListenerHandle {
+    /// Removes the listener. Events already being delivered may still reach
+    /// it; later ones do not.
+    pub fn unregister(self) {
+        if let Some(listeners) = self.listeners.upgrade() {
+            listeners.lock().retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// Future returned by a [`ReconnectOptions::with_on_established`] hook. It
+/// borrows the freshly established stream for its whole lifetime.
+pub type EstablishedFuture<'a> = Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'a>>;
+
+/// A hook registered through [`ReconnectOptions::with_on_established`], taking
+/// and returning the stream by value so that running it needs no bounds on `T`.
+pub(crate) type EstablishedHook<T> = Arc<dyn Fn(T) -> ConnectFuture<T> + Send + Sync>;
+
+/// A boxed future resolving to a connection.
+pub(crate) type ConnectFuture<T> = Pin<Box<dyn Future<Output = io::Result<T>> + Send>>;
+
+/// A connection produced by a connect sequence, with the retries the sequence
+/// did not use and the instant the connection was established.
+pub(crate) type Established<T> = (T, DurationIterator, Instant);
+
+/// A connect sequence, resolving to the new connection.
+pub(crate) type ConnectSequence<T> = ConnectFuture<Established<T>>;
+
+/// Spawns a [`ConnectSequence`] for [`ReconnectOptions::with_background_reconnect`].
+pub(crate) type SpawnSequence<T> = fn(ConnectSequence<T>) -> JoinHandle<io::Result<Established<T>>>;
+
 /// How [`StubbornIo`](crate::tokio::StubbornIo) should treat write requests issued
 /// while the underlying connection is down (or while a write itself revealed the
 /// disconnect).
@@ -82,10 +381,92 @@ pub enum WriteFailurePolicy {
     /// Pretend the write succeeded (`Poll::Ready(Ok(buf.len()))`) and drop the
     /// bytes on the floor. Schedules a reconnect attempt as a side effect, but
     /// the caller's framing layer will believe those bytes were delivered.
+    /// A write that finds reconnection has given up fails with
+    /// [`ErrorKind::NotConnected`](std::io::ErrorKind::NotConnected) instead.
     ///
     /// Only appropriate for fire-and-forget transports where loss is acceptable
     /// and back-pressure is not.
     DropAndNotify,
+    /// [`Self::Backpressure`], plus retransmission: the most recent successful
+    /// writes (up to `capacity` bytes) are retained and re-sent, oldest first, on
+    /// every new connection before any further caller write goes through.
+    ///
+    /// Bytes the kernel accepted shortly before the peer died are otherwise lost
+    /// silently; replaying them gives at-least-once delivery, so the receiving side
+    /// must tolerate duplicates. Retention is per message: a write the transport
+    /// accepts only in part, continued by the caller as `write_all` does, is kept
+    /// together with its continuation. The oldest whole messages are evicted
+    /// first, and a message larger than `capacity` is not retained, so a replay
+    /// never begins in the middle of a message that was written in one call.
+    /// Completion is reported through [`ReconnectEvent::Replayed`].
+    Replay {
+        /// Upper bound, in bytes, on the retained history.
+        capacity: usize,
+    },
+}
+
+/// What a [`RetryPolicy`] decided about the next connect attempt.
+///
+/// Non-exhaustive so new decisions can be added without breaking existing matches.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Try again after this delay. The retries iterator is not advanced.
+    Retry(Duration),
+    /// Stop reconnecting: `Exhausted` is emitted (with
+    /// [`ExhaustedReason::RetryPolicy`]) and the stream becomes terminal.
+    GiveUp,
+    /// Take the delay from the retries iterator, as without a policy.
+    UseDefault,
+}
+
+/// Picks the wait before each connect attempt from the error that made it
+/// necessary, set with [`ReconnectOptions::with_retry_policy`].
+///
+/// Consulted after every failed connect attempt, and after a lost connection
+/// for the wait before the first reconnect attempt. `attempt` is the number of
+/// the attempt being scheduled, as in [`ReconnectEvent::ReconnectScheduled`].
+/// For a lost connection, `error` is built from the [`DisconnectReason`]:
+/// the error kind for `IoError`, `UnexpectedEof` for `EndOfStream` and
+/// `TimedOut` for `IdleTimeout`. Reconnects the caller asked for (`Forced`,
+/// `ContextChanged`) are not failures and always use the retries iterator.
+///
+/// Errors that [`UnderlyingIo::is_fatal_connect_error`] classifies as fatal,
+/// and [`ReconnectOptions::with_exit_if_first_connect_fails`], end the
+/// sequence before the policy is asked.
+///
+/// Implemented for closures taking the same arguments.
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use std::time::Duration;
+/// use sdre_stubborn_io::ReconnectOptions;
+/// use sdre_stubborn_io::config::RetryDecision;
+///
+/// let options = ReconnectOptions::new().with_retry_policy(|error: &std::io::Error, _attempt| {
+///     match error.kind() {
+///         // Our own network is down; no point in hurrying.
+///         ErrorKind::HostUnreachable | ErrorKind::NetworkUnreachable => {
+///             RetryDecision::Retry(Duration::from_secs(30))
+///         }
+///         ErrorKind::PermissionDenied => RetryDecision::GiveUp,
+///         _ => RetryDecision::UseDefault,
+///     }
+/// });
+/// ```
+pub trait RetryPolicy: Send + Sync {
+    /// Decides what happens before connect attempt `attempt`, given the
+    /// `error` that made it necessary.
+    fn decide(&self, error: &io::Error, attempt: usize) -> RetryDecision;
+}
+
+impl<F> RetryPolicy for F
+where
+    F: Fn(&io::Error, usize) -> RetryDecision + Send + Sync,
+{
+    fn decide(&self, error: &io::Error, attempt: usize) -> RetryDecision {
+        self(error, attempt)
+    }
 }
 
 /// User specified options that control the behavior of the stubborn-io upon disconnect.
@@ -93,7 +474,11 @@ pub enum WriteFailurePolicy {
 /// All fields are crate-private; configure through the builder methods on this
 /// type (`with_*`). The struct is intentionally opaque so the builder remains
 /// the single supported API surface.
-pub struct ReconnectOptions {
+///
+/// `T` is the underlying stream type the options are tied to. It stays `()`,
+/// usable with any stream, until [`Self::with_on_established`] or
+/// [`Self::with_background_reconnect`] fixes it.
+pub struct ReconnectOptions<T = ()> {
     /// Represents a function that generates an `Iterator`
     /// to schedule the wait between reconnection attempts.
     pub(crate) retries_to_attempt_fn: Box<dyn Fn() -> DurationIterator + Send + Sync>,
@@ -102,9 +487,10 @@ pub struct ReconnectOptions {
     /// then no further reconnects will be attempted.
     pub(crate) exit_if_first_connect_fails: bool,
 
-    /// Invoked for every [`ReconnectEvent`] over the lifetime of this connection.
-    /// Defaults to a no-op. See [`Self::with_event_callback`].
-    pub(crate) event_callback: EventCallback,
+    /// Observers invoked for every [`ReconnectEvent`] over the lifetime of this
+    /// connection. Empty by default. See [`Self::with_event_callback`] and
+    /// [`Self::add_event_listener`].
+    pub(crate) events: Arc<EventListeners>,
 
     /// Identifier for this connection, used in log messages.
     ///
@@ -125,6 +511,39 @@ pub struct ReconnectOptions {
     /// failed. `None` (default) preserves the prior behavior of waiting forever
     /// on a single attempt.
     pub(crate) connect_timeout: Option<Duration>,
+
+    /// Post-connect hook; `None` (default) reports a connection as soon as
+    /// `establish` returns it.
+    pub(crate) on_established: Option<EstablishedHook<T>>,
+
+    /// Optional read idle timeout. When `Some(d)`, a pending read on a
+    /// connected stream that sees no bytes for `d` is treated as a disconnect.
+    /// `None` (default) waits on a silent peer forever.
+    pub(crate) read_idle_timeout: Option<Duration>,
+
+    /// Optional heartbeat: the payload written after each `interval` of write
+    /// inactivity, when driven through `StubbornIo::poll_heartbeat`.
+    pub(crate) heartbeat: Option<(Duration, Arc<[u8]>)>,
+
+    /// Runs connect sequences on a spawned task instead of only while the
+    /// stream is polled; `None` (default) runs them inline.
+    pub(crate) spawn_reconnect: Option<SpawnSequence<T>>,
+
+    /// Capacity of the owned event buffer set up by
+    /// [`Self::with_event_channel`]; `None` (default) disables it.
+    pub(crate) event_channel: Option<usize>,
+
+    /// How long a connection must last before the next disconnect starts the
+    /// retries iterator over; `None` (default) starts it over every time.
+    pub(crate) stability_window: Option<Duration>,
+
+    /// Consulted before the retries iterator; `None` (default) always uses the
+    /// iterator.
+    pub(crate) retry_policy: Option<Arc<dyn RetryPolicy>>,
+
+    /// How long a connect sequence may run before the stream gives up; `None`
+    /// (default) leaves that to the retries iterator.
+    pub(crate) max_disconnected_duration: Option<Duration>,
 }
 
 impl Default for ReconnectOptions {
@@ -143,13 +562,195 @@ impl ReconnectOptions {
         Self {
             retries_to_attempt_fn: Box::new(|| Box::new(ExpBackoffStrategy::default().into_iter())),
             exit_if_first_connect_fails: false,
-            event_callback: Arc::new(|_| {}),
+            events: Arc::new(EventListeners::new()),
             connection_name: Arc::from(""),
             write_failure_policy: WriteFailurePolicy::Backpressure,
             connect_timeout: None,
+            on_established: None,
+            read_idle_timeout: None,
+            heartbeat: None,
+            spawn_reconnect: None,
+            event_channel: None,
+            stability_window: None,
+            retry_policy: None,
+            max_disconnected_duration: None,
+        }
+    }
+
+    /// Sets a hook run against the stream after every successful
+    /// [`UnderlyingIo::establish`] (initial connect and every reconnect), before
+    /// the connection is reported as `Connected`.
+    ///
+    /// Use it for login lines, hello banners and similar per-connection
+    /// handshakes on the built-in stream types. The hook is part of the attempt:
+    /// it runs under [`Self::with_connect_timeout`], and an error it returns is
+    /// handled exactly like an `establish` error (`ConnectFailed`, then the next
+    /// step of the retries iterator).
+    ///
+    /// `T` is the underlying type of the stream these options are used with
+    /// (e.g. `tokio::net::TcpStream` for
+    /// [`StubbornTcpStream`](crate::StubbornTcpStream)), and must be `Send`; the
+    /// returned options are tied to it, so passing them to any other stream
+    /// type fails to compile.
+    ///
+    /// ```
+    /// use sdre_stubborn_io::ReconnectOptions;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::net::TcpStream;
+    ///
+    /// let options = ReconnectOptions::new().with_on_established(|stream: &mut TcpStream| {
+    ///     Box::pin(async move { stream.write_all(b"LOGIN feeder\n").await })
+    /// });
+    /// ```
+    ///
+    /// A hook for another stream type is rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use sdre_stubborn_io::{ReconnectOptions, StubbornTcpStream};
+    /// use tokio::net::UnixStream;
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// let options = ReconnectOptions::new()
+    ///     .with_on_established(|_: &mut UnixStream| Box::pin(async { Ok(()) }));
+    /// let addr = "127.0.0.1:30003".parse().unwrap();
+    /// let _stream = StubbornTcpStream::connect_with_options(addr, options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_on_established<T, F>(self, hook: F) -> ReconnectOptions<T>
+    where
+        T: UnderlyingIo + Send,
+        F: for<'a> Fn(&'a mut T) -> EstablishedFuture<'a> + Send + Sync + 'static,
+    {
+        self.into_typed::<T>().with_on_established(hook)
+    }
+
+    /// Runs each reconnect sequence on a spawned Tokio task instead of only
+    /// while the stream is being polled.
+    ///
+    /// This does not detect disconnects. The task is spawned only once a read
+    /// or write has seen the connection drop; a connection lost while nobody
+    /// polls the stream goes unnoticed until the next read or write, exactly as
+    /// without this option. Keep a read pending if the loss must be detected
+    /// promptly.
+    ///
+    /// What it changes is the reconnect that follows. By default a disconnect
+    /// seen by a write that then returns (under
+    /// [`WriteFailurePolicy::DropAndNotify`]) or by a read nobody repeats waits
+    /// for the next poll before its backoff timers and attempts make progress.
+    /// With this set they run on schedule regardless, with their events, and
+    /// the stream picks up the new connection on its next poll. The task is
+    /// aborted when the stream is shut down or dropped.
+    ///
+    /// The returned options are tied to the underlying stream type `T`, which
+    /// must be `Send` to move to the task.
+    ///
+    /// ```
+    /// use sdre_stubborn_io::ReconnectOptions;
+    /// use tokio::net::TcpStream;
+    ///
+    /// let options = ReconnectOptions::new().with_background_reconnect::<TcpStream>(true);
+    /// ```
+    #[must_use]
+    pub fn with_background_reconnect<T>(self, value: bool) -> ReconnectOptions<T>
+    where
+        T: UnderlyingIo + Send,
+    {
+        self.into_typed::<T>().with_background_reconnect(value)
+    }
+
+    /// Ties these options to the stream type `T`.
+    ///
+    /// Nothing is discarded: untyped options never hold a hook or a spawner,
+    /// since both setters require `T: UnderlyingIo`, which `()` is not, and
+    /// typed options have no way back to `()`.
+    fn into_typed<T>(self) -> ReconnectOptions<T> {
+        let Self {
+            retries_to_attempt_fn,
+            exit_if_first_connect_fails,
+            events,
+            connection_name,
+            write_failure_policy,
+            connect_timeout,
+            on_established: None,
+            read_idle_timeout,
+            heartbeat,
+            spawn_reconnect: None,
+            event_channel,
+            stability_window,
+            retry_policy,
+            max_disconnected_duration,
+        } = self
+        else {
+            unreachable!("untyped ReconnectOptions cannot hold a hook or spawner");
+        };
+        ReconnectOptions {
+            retries_to_attempt_fn,
+            exit_if_first_connect_fails,
+            events,
+            connection_name,
+            write_failure_policy,
+            connect_timeout,
+            on_established: None,
+            read_idle_timeout,
+            heartbeat,
+            spawn_reconnect: None,
+            event_channel,
+            stability_window,
+            retry_policy,
+            max_disconnected_duration,
         }
     }
+}
+
+impl<T: UnderlyingIo> From<ReconnectOptions> for ReconnectOptions<T> {
+    fn from(options: ReconnectOptions) -> Self {
+        options.into_typed()
+    }
+}
+
+impl<T: UnderlyingIo + Send> ReconnectOptions<T> {
+    /// [`ReconnectOptions::with_on_established`] for options already tied to
+    /// `T`, replacing any hook set earlier.
+    ///
+    /// Typed options cannot be tied to another stream type, so a hook is never
+    /// dropped by a later setter:
+    ///
+    /// ```compile_fail
+    /// use sdre_stubborn_io::ReconnectOptions;
+    /// use tokio::net::{TcpStream, UnixStream};
+    ///
+    /// let options = ReconnectOptions::new()
+    ///     .with_on_established(|_: &mut TcpStream| Box::pin(async { Ok(()) }))
+    ///     .with_background_reconnect::<UnixStream>(true);
+    /// ```
+    #[must_use]
+    pub fn with_on_established<F>(mut self, hook: F) -> Self
+    where
+        F: for<'a> Fn(&'a mut T) -> EstablishedFuture<'a> + Send + Sync + 'static,
+    {
+        let hook = Arc::new(hook);
+        self.on_established = Some(Arc::new(move |mut io: T| -> ConnectFuture<T> {
+            let hook = Arc::clone(&hook);
+            Box::pin(async move {
+                hook(&mut io).await?;
+                Ok(io)
+            })
+        }));
+        self
+    }
+
+    /// [`ReconnectOptions::with_background_reconnect`] for options already tied
+    /// to `T`.
+    #[must_use]
+    pub fn with_background_reconnect(mut self, value: bool) -> Self {
+        self.spawn_reconnect = value.then_some(tokio::spawn as SpawnSequence<T>);
+        self
+    }
+}
 
+impl<T> ReconnectOptions<T> {
     /// This convenience function allows the user to provide any function that returns a value
     /// that is convertible into an iterator, such as an actual iterator or a `Vec`.
     ///
@@ -188,18 +789,121 @@ impl ReconnectOptions {
         self
     }
 
-    /// Sets the single observer invoked for every [`ReconnectEvent`].
+    /// Only starts the retries iterator over once a connection has stayed up
+    /// for `window`.
+    ///
+    /// By default every disconnect gets a fresh iterator, so a connection that
+    /// drops shortly after every reconnect is retried at the shortest delay
+    /// forever. With a window set, a connection lost before `window` has
+    /// passed, for whatever reason, continues with the delays the previous
+    /// sequence did not use. A finite iterator therefore also bounds the
+    /// reconnects of a connection that never stays up that long.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use sdre_stubborn_io::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_stability_window(Duration::from_secs(30));
+    /// ```
+    #[must_use]
+    pub const fn with_stability_window(mut self, window: Duration) -> Self {
+        self.stability_window = Some(window);
+        self
+    }
+
+    /// Sets the [`RetryPolicy`] that picks the wait before each connect attempt
+    /// based on the error that made it necessary, falling back to the retries
+    /// iterator when it returns [`RetryDecision::UseDefault`].
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Gives up once the stream has been disconnected for `duration`, however
+    /// many retries are left.
+    ///
+    /// The clock starts when the connection is lost (or, for the initial
+    /// connect, when the stream is created). When it runs out, the pending wait
+    /// or attempt is abandoned. [`ReconnectEvent::Exhausted`] is emitted with
+    /// [`ExhaustedReason::MaxDisconnectedDuration`], and the stream becomes
+    /// terminal (an initial connect through
+    /// [`StubbornIo::connect_with_options`](crate::tokio::StubbornIo::connect_with_options)
+    /// fails with `TimedOut`). Without
+    /// [`Self::with_background_reconnect`], this happens on the next read or
+    /// write after the deadline.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use sdre_stubborn_io::ReconnectOptions;
+    ///
+    /// let options =
+    ///     ReconnectOptions::new().with_max_disconnected_duration(Duration::from_secs(600));
+    /// ```
+    #[must_use]
+    pub const fn with_max_disconnected_duration(mut self, duration: Duration) -> Self {
+        self.max_disconnected_duration = Some(duration);
+        self
+    }
+
+    /// Sets the observer invoked for every [`ReconnectEvent`], replacing any
+    /// callback set earlier. Listeners added with [`Self::add_event_listener`]
+    /// are kept, and are invoked after it.
     ///
     /// Replaces the prior `with_on_connect_callback` /
     /// `with_on_disconnect_callback` / `with_on_connect_fail_callback` trio.
-    /// The callback is stored in an `Arc` and is cloned into each scheduled
-    /// reconnect future, so it must be `Send + Sync + 'static`.
+    /// The callback is stored in an `Arc` and is shared with reconnect
+    /// futures, so it must be `Send + Sync + 'static`.
     #[must_use]
     pub fn with_event_callback(
-        mut self,
+        self,
         cb: impl for<'a> Fn(ReconnectEvent<'a>) + Send + Sync + 'static,
     ) -> Self {
-        self.event_callback = Arc::new(cb);
+        self.events.set_callback(Arc::new(cb));
+        self
+    }
+
+    /// Adds an observer invoked for every [`ReconnectEvent`], in addition to
+    /// the [`Self::with_event_callback`] one and any other listeners, so
+    /// independent layers (logging, metrics, alerting) can each subscribe.
+    ///
+    /// Listeners receive each event in registration order. A listener that
+    /// panics is logged and skipped for that event; the others still receive
+    /// it (unless the binary is built with `panic = "abort"`). The returned
+    /// handle unregisters the listener; it stays valid after the options have
+    /// been moved into a stream.
+    ///
+    /// ```
+    /// use sdre_stubborn_io::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new();
+    /// let metrics = options.add_event_listener(|ev| println!("metrics: {ev:?}"));
+    /// let _alerts = options.add_event_listener(|ev| println!("alerts: {ev:?}"));
+    /// // ... later, when metrics are no longer collected:
+    /// metrics.unregister();
+    /// ```
+    pub fn add_event_listener(
+        &self,
+        listener: impl for<'a> Fn(ReconnectEvent<'a>) + Send + Sync + 'static,
+    ) -> EventListenerHandle {
+        self.events.add(Arc::new(listener))
+    }
+
+    /// Additionally delivers every event as an [`OwnedReconnectEvent`] through
+    /// a `futures_core::Stream`, for consumers that want to `.await` while
+    /// handling events or keep them around. Take the stream with
+    /// [`StubbornIo::take_event_stream`](crate::tokio::StubbornIo::take_event_stream);
+    /// events are buffered from the moment the stream is constructed, so those
+    /// of the initial connect are not missed. The callback from
+    /// [`Self::with_event_callback`] is still invoked.
+    ///
+    /// Up to `capacity` (at least 1) events are buffered. Emitting never waits
+    /// for the consumer: once the buffer is full, the oldest event is dropped
+    /// and counted in
+    /// [`ReconnectEventStream::lagged`](crate::tokio::ReconnectEventStream::lagged).
+    #[must_use]
+    pub const fn with_event_channel(mut self, capacity: usize) -> Self {
+        self.event_channel = Some(capacity);
         self
     }
 
@@ -231,6 +935,44 @@ impl ReconnectOptions {
         self.connect_timeout = timeout;
         self
     }
+
+    /// Treats a connection on which no bytes arrive for `timeout` as dead.
+    ///
+    /// A peer that vanishes without a FIN or RST (NAT entry expiry, a router
+    /// reboot) otherwise leaves reads pending forever. With this set, a read
+    /// that has been waiting on a `Connected` stream for `timeout` since the
+    /// last received bytes (or since the connection was established) emits
+    /// [`ReconnectEvent::IdleTimeout`] and goes through the regular disconnect
+    /// and reconnect path. Only meaningful for feeds that are never legitimately
+    /// silent that long. Applies to datagram receives as well.
+    ///
+    /// Silence is only observed while a read is in progress; a stream that is
+    /// never read from never times out.
+    #[must_use]
+    pub const fn with_read_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.read_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Writes `payload` (e.g. `b"\n"` or a protocol keepalive frame) after every
+    /// `interval` without a successful write, so idle-dropping receivers keep
+    /// the connection and a dead peer is noticed without waiting for real data.
+    ///
+    /// Heartbeats are only sent while the caller drives
+    /// [`StubbornIo::poll_heartbeat`](crate::tokio::StubbornIo::poll_heartbeat)
+    /// (or its `async` form, `heartbeat()`, also available on
+    /// [`StubbornWriteHalf`](crate::tokio::StubbornWriteHalf)) alongside its own
+    /// writes, typically as one more branch of the writer's `tokio::select!`.
+    /// Setting this alone sends nothing. A heartbeat is never interleaved inside a caller write:
+    /// it waits while the caller's last write was short (a `write_all` in
+    /// progress), and once started it is completed before the next caller write
+    /// goes through. A heartbeat write that reveals a disconnect goes through
+    /// the normal reconnect path.
+    #[must_use]
+    pub fn with_heartbeat(mut self, interval: Duration, payload: impl AsRef<[u8]>) -> Self {
+        self.heartbeat = Some((interval, Arc::from(payload.as_ref())));
+        self
+    }
 }
 
 /// Build the formatted log prefix for a given connection name.
@@ -55,6 +55,8 @@
 //! ```
 
 pub mod config;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod strategies;
 
 // in the future, there may be a mod for synchronous regular io too, which is why
@@ -64,4 +66,14 @@ pub mod tokio;
 #[doc(inline)]
 pub use self::config::ReconnectOptions;
 #[doc(inline)]
+pub use self::tokio::StubbornTcpHostStream;
+#[doc(inline)]
 pub use self::tokio::StubbornTcpStream;
+#[cfg(feature = "tls")]
+#[doc(inline)]
+pub use self::tokio::StubbornTlsStream;
+#[doc(inline)]
+pub use self::tokio::StubbornUdpSocket;
+#[cfg(unix)]
+#[doc(inline)]
+pub use self::tokio::StubbornUnixStream;
@@ -0,0 +1,217 @@
+//! `OpenMetrics` text exposition of [`StubbornIo`] statistics. Requires the
+//! `metrics` feature.
+//!
+//! Register each stream with a [`MetricsRegistry`] and serve
+//! [`MetricsRegistry::render`] (with [`CONTENT_TYPE`]) from an existing admin
+//! or health endpoint; no HTTP server is included.
+//!
+//! ```
+//! use sdre_stubborn_io::metrics::MetricsRegistry;
+//!
+//! let registry = MetricsRegistry::new();
+//! // registry.register(&stream); for every stream, then on each scrape:
+//! let body = registry.render();
+//! assert!(body.ends_with("# EOF\n"));
+//! ```
+//!
+//! Every metric carries a `connection` label holding the
+//! [`connection_name`](crate::ReconnectOptions::with_connection_name), so
+//! streams registered with the same registry should have distinct names.
+
+use crate::tokio::{ConnectionStats, StatsSnapshot, StubbornIo, UnderlyingIo};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+
+/// `Content-Type` header value for the output of [`MetricsRegistry::render`].
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+type Entries = Vec<(Arc<str>, Weak<ConnectionStats>)>;
+
+/// A set of streams whose statistics are rendered together.
+///
+/// Cheap to clone; clones share the same set. Streams are held weakly and
+/// disappear from the output once dropped.
+#[derive(Clone, Default)]
+pub struct MetricsRegistry {
+    streams: Arc<Mutex<Entries>>,
+}
+
+impl MetricsRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn streams(&self) -> MutexGuard<'_, Entries> {
+        self.streams.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds `stream` under its connection name.
+    pub fn register<T: UnderlyingIo>(&self, stream: &StubbornIo<T>) {
+        let stats = Arc::downgrade(&stream.stats_handle());
+        self.streams()
+            .push((Arc::clone(stream.connection_name()), stats));
+    }
+
+    /// Renders the statistics of every registered stream that is still alive
+    /// in the `OpenMetrics` text format, terminated by `# EOF`.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let snapshots: Vec<(Arc<str>, StatsSnapshot)> = {
+            let mut streams = self.streams();
+            streams.retain(|(_, stats)| stats.strong_count() > 0);
+            streams
+                .iter()
+                .filter_map(|(name, stats)| Some((Arc::clone(name), stats.upgrade()?.snapshot())))
+                .collect()
+        };
+
+        let mut out = String::new();
+        write_counters(&mut out, &snapshots);
+        write_outage_histogram(&mut out, &snapshots);
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// The connected gauge and the counters.
+fn write_counters(out: &mut String, snapshots: &[(Arc<str>, StatsSnapshot)]) {
+    family(
+        out,
+        "stubborn_io_connected",
+        "gauge",
+        "Whether the connection is currently established.",
+    );
+    for (name, stats) in snapshots {
+        sample(
+            out,
+            "stubborn_io_connected",
+            name,
+            &[],
+            u8::from(stats.uptime.is_some()),
+        );
+    }
+    family(
+        out,
+        "stubborn_io_reconnects",
+        "counter",
+        "Connections established after the first one.",
+    );
+    for (name, stats) in snapshots {
+        sample(
+            out,
+            "stubborn_io_reconnects_total",
+            name,
+            &[],
+            stats.reconnects,
+        );
+    }
+    family(
+        out,
+        "stubborn_io_connect_failures",
+        "counter",
+        "Failed connect attempts by error kind.",
+    );
+    for (name, stats) in snapshots {
+        for (kind, count) in &stats.failed_attempts_by_kind {
+            let kind = format!("{kind:?}");
+            sample(
+                out,
+                "stubborn_io_connect_failures_total",
+                name,
+                &[("kind", &kind)],
+                count,
+            );
+        }
+    }
+    family(
+        out,
+        "stubborn_io_dropped_bytes",
+        "counter",
+        "Bytes discarded while disconnected under WriteFailurePolicy::DropAndNotify.",
+    );
+    for (name, stats) in snapshots {
+        sample(
+            out,
+            "stubborn_io_dropped_bytes_total",
+            name,
+            &[],
+            stats.bytes_dropped,
+        );
+    }
+}
+
+fn write_outage_histogram(out: &mut String, snapshots: &[(Arc<str>, StatsSnapshot)]) {
+    family(
+        out,
+        "stubborn_io_disconnect_duration_seconds",
+        "histogram",
+        "Time from losing a connection to establishing the next one.",
+    );
+    let _ = writeln!(
+        out,
+        "# UNIT stubborn_io_disconnect_duration_seconds seconds"
+    );
+    for (name, stats) in snapshots {
+        let outages = &stats.outages;
+        for (bound, count) in &outages.buckets {
+            let le = format!("{:?}", bound.as_secs_f64());
+            sample(
+                out,
+                "stubborn_io_disconnect_duration_seconds_bucket",
+                name,
+                &[("le", &le)],
+                count,
+            );
+        }
+        sample(
+            out,
+            "stubborn_io_disconnect_duration_seconds_bucket",
+            name,
+            &[("le", "+Inf")],
+            outages.count,
+        );
+        sample(
+            out,
+            "stubborn_io_disconnect_duration_seconds_count",
+            name,
+            &[],
+            outages.count,
+        );
+        sample(
+            out,
+            "stubborn_io_disconnect_duration_seconds_sum",
+            name,
+            &[],
+            format_args!("{:?}", outages.sum.as_secs_f64()),
+        );
+    }
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "# HELP {name} {help}");
+}
+
+fn sample(
+    out: &mut String,
+    metric: &str,
+    connection: &str,
+    labels: &[(&str, &str)],
+    value: impl std::fmt::Display,
+) {
+    let _ = write!(out, "{metric}{{connection=\"{}\"", escape(connection));
+    for (label, label_value) in labels {
+        let _ = write!(out, ",{label}=\"{}\"", escape(label_value));
+    }
+    let _ = writeln!(out, "}} {value}");
+}
+
+/// Escapes a label value as required by the text format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
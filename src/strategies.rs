@@ -2,6 +2,27 @@
 use rand::{RngExt, SeedableRng, rngs::StdRng};
 use std::time::Duration;
 
+/// Seeded RNG if a seed was given, otherwise one seeded from the thread RNG.
+fn new_rng(seed: Option<u64>) -> StdRng {
+    seed.map_or_else(
+        || {
+            let mut thread_rng = rand::rng();
+            StdRng::from_rng(&mut thread_rng)
+        },
+        StdRng::seed_from_u64,
+    )
+}
+
+/// Clamp to a finite, non-negative value before constructing a Duration.
+/// `Duration::from_secs_f64` panics on NaN, negative, or > `Duration::MAX`.
+fn secs_to_duration(secs: f64) -> Duration {
+    if secs.is_finite() && secs >= 0.0 {
+        Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+    } else {
+        Duration::MAX
+    }
+}
+
 /// Type used for defining the exponential backoff strategy.
 ///
 /// # Examples
@@ -76,13 +97,7 @@ impl IntoIterator for ExpBackoffStrategy {
 
     fn into_iter(self) -> Self::IntoIter {
         let init = self.min.as_secs_f64();
-        let rng = self.seed.map_or_else(
-            || {
-                let mut thread_rng = rand::rng();
-                StdRng::from_rng(&mut thread_rng)
-            },
-            StdRng::seed_from_u64,
-        );
+        let rng = new_rng(self.seed);
 
         ExpBackoffIter {
             strategy: self,
@@ -108,22 +123,408 @@ impl Iterator for ExpBackoffIter {
         let base = self.init * self.strategy.factor.powf(f64::from(self.pow));
         #[allow(clippy::suboptimal_flops)] // FMA changes bit-exactness; tests pin specific values.
         let jitter = base * self.strategy.jitter * (self.rng.random::<f64>() * 2.0 - 1.0);
-        let secs = base + jitter;
-        // Clamp to a finite, non-negative value before constructing a Duration.
-        // `Duration::from_secs_f64` panics on NaN, negative, or > Duration::MAX.
-        let current = if secs.is_finite() && secs >= 0.0 {
-            Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
-        } else {
-            Duration::MAX
-        };
+        let current = secs_to_duration(base + jitter);
         self.pow = self.pow.saturating_add(1);
         Some(self.strategy.max.map_or(current, |max| max.min(current)))
     }
 }
 
+/// "Decorrelated jitter" backoff: each wait is drawn uniformly between `base`
+/// and three times the previous wait, capped at `cap`.
+///
+/// Unlike [`ExpBackoffStrategy`], whose jitter is a small band around a fixed
+/// curve, successive waits here are random walks of their own, so clients
+/// that lost their connection at the same moment drift apart instead of
+/// retrying in step.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use sdre_stubborn_io::{ReconnectOptions, strategies::DecorrelatedJitterStrategy};
+///
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     DecorrelatedJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(60))
+/// });
+/// ```
+pub struct DecorrelatedJitterStrategy {
+    base: Duration,
+    cap: Duration,
+    seed: Option<u64>,
+}
+
+impl DecorrelatedJitterStrategy {
+    /// Construct a new decorrelated-jitter strategy that never waits less
+    /// than `base` or more than `cap`.
+    #[must_use]
+    pub const fn new(base: Duration, cap: Duration) -> Self {
+        Self {
+            base,
+            cap,
+            seed: None,
+        }
+    }
+
+    /// Set the seed used to generate jitter. Otherwise, will set RNG via entropy.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl IntoIterator for DecorrelatedJitterStrategy {
+    type Item = Duration;
+    type IntoIter = DecorrelatedJitterIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let base = self.base.as_secs_f64();
+        DecorrelatedJitterIter {
+            base,
+            cap: self.cap,
+            prev: base,
+            rng: new_rng(self.seed),
+        }
+    }
+}
+
+/// Iterator class for [`DecorrelatedJitterStrategy`].
+pub struct DecorrelatedJitterIter {
+    base: f64,
+    cap: Duration,
+    prev: f64,
+    rng: StdRng,
+}
+
+impl Iterator for DecorrelatedJitterIter {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let upper = (self.prev * 3.0).max(self.base);
+        #[allow(clippy::suboptimal_flops)] // FMA changes bit-exactness; tests pin specific values.
+        let secs = self.base + self.rng.random::<f64>() * (upper - self.base);
+        let current = self.cap.min(secs_to_duration(secs));
+        self.prev = current.as_secs_f64();
+        Some(current)
+    }
+}
+
+/// "Full jitter" backoff: each wait is drawn uniformly between zero and an
+/// exponential ceiling, `base * 2^n` for the n-th retry, capped at `cap`.
+///
+/// Spreads a crowd of reconnecting clients over the whole window rather than
+/// around its end, at the cost of some retries coming sooner than `base`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use sdre_stubborn_io::{ReconnectOptions, strategies::FullJitterStrategy};
+///
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     FullJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(60))
+/// });
+/// ```
+pub struct FullJitterStrategy {
+    base: Duration,
+    cap: Duration,
+    seed: Option<u64>,
+}
+
+impl FullJitterStrategy {
+    /// Construct a new full-jitter strategy whose ceiling starts at `base`,
+    /// doubles with every retry and never exceeds `cap`.
+    #[must_use]
+    pub const fn new(base: Duration, cap: Duration) -> Self {
+        Self {
+            base,
+            cap,
+            seed: None,
+        }
+    }
+
+    /// Set the seed used to generate jitter. Otherwise, will set RNG via entropy.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl IntoIterator for FullJitterStrategy {
+    type Item = Duration;
+    type IntoIter = FullJitterIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        FullJitterIter {
+            base: self.base.as_secs_f64(),
+            cap: self.cap.as_secs_f64(),
+            pow: 0,
+            rng: new_rng(self.seed),
+        }
+    }
+}
+
+/// Iterator class for [`FullJitterStrategy`].
+pub struct FullJitterIter {
+    base: f64,
+    cap: f64,
+    pow: u32,
+    rng: StdRng,
+}
+
+impl Iterator for FullJitterIter {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ceiling = self.cap.min(self.base * f64::from(self.pow).exp2());
+        let secs = self.rng.random::<f64>() * ceiling;
+        self.pow = self.pow.saturating_add(1);
+        Some(secs_to_duration(secs))
+    }
+}
+
+/// Combinators for retry schedules.
+///
+/// Implemented for everything that turns into an iterator of delays: the
+/// strategies in this module, `Vec<Duration>`, arrays, and plain iterators.
+/// Every combinator returns another such schedule, so the result can be
+/// returned straight from
+/// [`ReconnectOptions::with_retries_generator`](crate::ReconnectOptions::with_retries_generator).
+///
+/// `Iterator::chain` and `Ord::clamp` share names with [`Self::chain`] and
+/// [`Self::clamp`]; on a plain iterator or a `Vec`, call those two as
+/// `RetryStrategyExt::chain(schedule, other)`. The schedules returned by this
+/// trait are not iterators themselves, so chaining after another combinator
+/// needs no such qualification.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use sdre_stubborn_io::ReconnectOptions;
+/// use sdre_stubborn_io::strategies::{ExpBackoffStrategy, RetryStrategyExt};
+///
+/// // Retry once straight away, then back off exponentially for at most ten
+/// // attempts, then keep trying once a minute forever.
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     ExpBackoffStrategy::new(Duration::from_secs(1), 2.0, 0.05)
+///         .max_attempts(10)
+///         .clamp(Duration::from_millis(500), Duration::from_secs(30))
+///         .with_initial_immediate()
+///         .then_repeat(Duration::from_secs(60))
+/// });
+/// ```
+pub trait RetryStrategyExt: IntoIterator<Item = Duration> + Sized {
+    /// Stops after at most `n` delays.
+    fn max_attempts(self, n: usize) -> MaxAttempts<Self> {
+        MaxAttempts { inner: self, n }
+    }
+
+    /// Stops before the delay that would take the sum of all delays so far
+    /// past `budget`.
+    fn max_elapsed(self, budget: Duration) -> MaxElapsed<Self> {
+        MaxElapsed {
+            inner: self,
+            budget,
+        }
+    }
+
+    /// Continues with the delays of `other` once this schedule ends.
+    fn chain<O>(self, other: O) -> Chain<Self, O>
+    where
+        O: IntoIterator<Item = Duration>,
+    {
+        Chain {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Continues with `delay`, forever, once this schedule ends.
+    fn then_repeat(self, delay: Duration) -> ThenRepeat<Self> {
+        ThenRepeat { inner: self, delay }
+    }
+
+    /// Adds a retry with no delay ahead of this schedule.
+    fn with_initial_immediate(self) -> InitialImmediate<Self> {
+        InitialImmediate { inner: self }
+    }
+
+    /// Limits every delay to the range `min..=max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    fn clamp(self, min: Duration, max: Duration) -> Clamp<Self> {
+        assert!(min <= max, "clamp: min ({min:?}) > max ({max:?})");
+        Clamp {
+            inner: self,
+            min,
+            max,
+        }
+    }
+}
+
+impl<S> RetryStrategyExt for S where S: IntoIterator<Item = Duration> {}
+
+/// Schedule returned by [`RetryStrategyExt::max_attempts`].
+#[derive(Debug, Clone)]
+pub struct MaxAttempts<S> {
+    inner: S,
+    n: usize,
+}
+
+impl<S: IntoIterator<Item = Duration>> IntoIterator for MaxAttempts<S> {
+    type Item = Duration;
+    type IntoIter = std::iter::Take<S::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter().take(self.n)
+    }
+}
+
+/// Schedule returned by [`RetryStrategyExt::max_elapsed`].
+#[derive(Debug, Clone)]
+pub struct MaxElapsed<S> {
+    inner: S,
+    budget: Duration,
+}
+
+impl<S: IntoIterator<Item = Duration>> IntoIterator for MaxElapsed<S> {
+    type Item = Duration;
+    type IntoIter = MaxElapsedIter<S::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MaxElapsedIter {
+            inner: Some(self.inner.into_iter()),
+            remaining: self.budget,
+        }
+    }
+}
+
+/// Iterator class for [`MaxElapsed`].
+#[derive(Debug, Clone)]
+pub struct MaxElapsedIter<I> {
+    /// `None` once the budget has run out.
+    inner: Option<I>,
+    remaining: Duration,
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for MaxElapsedIter<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let delay = self.inner.as_mut()?.next()?;
+        if let Some(remaining) = self.remaining.checked_sub(delay) {
+            self.remaining = remaining;
+            Some(delay)
+        } else {
+            self.inner = None;
+            None
+        }
+    }
+}
+
+/// Schedule returned by [`RetryStrategyExt::chain`].
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> IntoIterator for Chain<A, B>
+where
+    A: IntoIterator<Item = Duration>,
+    B: IntoIterator<Item = Duration>,
+{
+    type Item = Duration;
+    type IntoIter = std::iter::Chain<A::IntoIter, B::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iterator::chain(self.first.into_iter(), self.second)
+    }
+}
+
+/// Schedule returned by [`RetryStrategyExt::then_repeat`].
+#[derive(Debug, Clone)]
+pub struct ThenRepeat<S> {
+    inner: S,
+    delay: Duration,
+}
+
+impl<S: IntoIterator<Item = Duration>> IntoIterator for ThenRepeat<S> {
+    type Item = Duration;
+    type IntoIter = std::iter::Chain<S::IntoIter, std::iter::Repeat<Duration>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iterator::chain(self.inner.into_iter(), std::iter::repeat(self.delay))
+    }
+}
+
+/// Schedule returned by [`RetryStrategyExt::with_initial_immediate`].
+#[derive(Debug, Clone)]
+pub struct InitialImmediate<S> {
+    inner: S,
+}
+
+impl<S: IntoIterator<Item = Duration>> IntoIterator for InitialImmediate<S> {
+    type Item = Duration;
+    type IntoIter = std::iter::Chain<std::iter::Once<Duration>, S::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iterator::chain(std::iter::once(Duration::ZERO), self.inner)
+    }
+}
+
+/// Schedule returned by [`RetryStrategyExt::clamp`].
+#[derive(Debug, Clone)]
+pub struct Clamp<S> {
+    inner: S,
+    min: Duration,
+    max: Duration,
+}
+
+impl<S: IntoIterator<Item = Duration>> IntoIterator for Clamp<S> {
+    type Item = Duration;
+    type IntoIter = ClampIter<S::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ClampIter {
+            inner: self.inner.into_iter(),
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
+/// Iterator class for [`Clamp`].
+#[derive(Debug, Clone)]
+pub struct ClampIter<I> {
+    inner: I,
+    min: Duration,
+    max: Duration,
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for ClampIter<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|delay| delay.clamp(self.min, self.max))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::ExpBackoffStrategy;
+    use super::{
+        DecorrelatedJitterStrategy, ExpBackoffStrategy, FullJitterStrategy, RetryStrategyExt,
+    };
     use std::time::Duration;
 
     #[test]
@@ -160,4 +561,131 @@ mod test {
             assert!(value.total_cmp(&expected).is_eq(), "{value} != {expected}");
         }
     }
+
+    #[test]
+    fn test_decorrelated_jitter_values() {
+        let mut backoff_iter =
+            DecorrelatedJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(60))
+                .with_seed(0)
+                .into_iter();
+        let expected_values = [
+            2.462_226_832,
+            5.939_843_074,
+            1.434_694_581,
+            2.930_111_121,
+            3.042_396_626,
+            7.274_084_166,
+            5.553_762_295,
+            13.430_574_149,
+            30.442_797_575,
+        ];
+        for expected in expected_values {
+            let value = backoff_iter.next().unwrap().as_secs_f64();
+            assert!(value.total_cmp(&expected).is_eq(), "{value} != {expected}");
+        }
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_max_value() {
+        let backoff_iter =
+            DecorrelatedJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(3))
+                .with_seed(0)
+                .into_iter();
+        for value in backoff_iter.take(50) {
+            assert!(value >= Duration::from_secs(1), "{value:?}");
+            assert!(value <= Duration::from_secs(3), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_full_jitter_values() {
+        let mut backoff_iter =
+            FullJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(60))
+                .with_seed(0)
+                .into_iter();
+        let expected_values = [
+            0.731_113_416,
+            1.546_920_369,
+            0.103_378_537,
+            4.673_274_095,
+            4.194_730_121,
+            24.703_580_984,
+            13.121_814_64,
+            47.622_807_4,
+            44.960_305_746,
+        ];
+        for expected in expected_values {
+            let value = backoff_iter.next().unwrap().as_secs_f64();
+            assert!(value.total_cmp(&expected).is_eq(), "{value} != {expected}");
+        }
+    }
+
+    #[test]
+    fn test_full_jitter_max_value() {
+        let backoff_iter = FullJitterStrategy::new(Duration::from_secs(1), Duration::from_secs(8))
+            .with_seed(0)
+            .into_iter();
+        for value in backoff_iter.take(100) {
+            assert!(value < Duration::from_secs(8), "{value:?}");
+        }
+    }
+
+    fn secs(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_secs).collect()
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let values: Vec<_> = ExpBackoffStrategy::new(Duration::from_secs(1), 2., 0.0)
+            .max_attempts(3)
+            .into_iter()
+            .collect();
+        assert_eq!(values, secs(&[1, 2, 4]));
+    }
+
+    #[test]
+    fn test_max_elapsed() {
+        let values: Vec<_> = ExpBackoffStrategy::new(Duration::from_secs(1), 2., 0.0)
+            .max_elapsed(Duration::from_secs(10))
+            .into_iter()
+            .collect();
+        // 1 + 2 + 4 = 7; another 8 would take the total to 15.
+        assert_eq!(values, secs(&[1, 2, 4]));
+
+        let exact: Vec<_> = secs(&[5, 5, 1])
+            .max_elapsed(Duration::from_secs(10))
+            .into_iter()
+            .collect();
+        assert_eq!(exact, secs(&[5, 5]));
+    }
+
+    #[test]
+    fn test_chain_and_then_repeat() {
+        let values: Vec<_> = secs(&[1, 2])
+            .max_attempts(2)
+            .chain(secs(&[7]))
+            .then_repeat(Duration::from_secs(30))
+            .into_iter()
+            .take(5)
+            .collect();
+        assert_eq!(values, secs(&[1, 2, 7, 30, 30]));
+    }
+
+    #[test]
+    fn test_with_initial_immediate() {
+        let values: Vec<_> = secs(&[1, 2]).with_initial_immediate().into_iter().collect();
+        assert_eq!(values, secs(&[0, 1, 2]));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let values: Vec<_> = RetryStrategyExt::clamp(
+            secs(&[0, 3, 60]),
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+        )
+        .into_iter()
+        .collect();
+        assert_eq!(values, secs(&[1, 3, 10]));
+    }
 }
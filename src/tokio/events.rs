@@ -0,0 +1,118 @@
+//! Bounded buffer backing [`ReconnectOptions::with_event_channel`](crate::ReconnectOptions::with_event_channel).
+
+use crate::config::{OwnedReconnectEvent, ReconnectEvent};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+struct Buffer {
+    events: VecDeque<OwnedReconnectEvent>,
+    capacity: usize,
+    lagged: u64,
+    /// Set once the sending side is gone; no more events will arrive.
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+type Shared = Arc<Mutex<Buffer>>;
+
+fn lock(shared: &Shared) -> MutexGuard<'_, Buffer> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Creates the sending side, installed into the event callback, and the
+/// stream handed to the caller.
+pub(super) fn channel(
+    capacity: usize,
+    connection_name: Arc<str>,
+) -> (EventSender, ReconnectEventStream) {
+    let shared = Arc::new(Mutex::new(Buffer {
+        events: VecDeque::new(),
+        capacity: capacity.max(1),
+        lagged: 0,
+        closed: false,
+        waker: None,
+    }));
+    let sender = EventSender {
+        shared: Arc::clone(&shared),
+        connection_name,
+    };
+    (sender, ReconnectEventStream { shared })
+}
+
+pub(super) struct EventSender {
+    shared: Shared,
+    connection_name: Arc<str>,
+}
+
+impl EventSender {
+    /// Buffers an owned copy of `event`, dropping the oldest buffered event if
+    /// the consumer has fallen `capacity` events behind.
+    pub(super) fn send(&self, event: &ReconnectEvent<'_>) {
+        let event = OwnedReconnectEvent::new(&self.connection_name, event);
+        let mut buffer = lock(&self.shared);
+        if buffer.events.len() == buffer.capacity {
+            buffer.events.pop_front();
+            buffer.lagged += 1;
+        }
+        buffer.events.push_back(event);
+        let waker = buffer.waker.take();
+        drop(buffer);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        let mut buffer = lock(&self.shared);
+        buffer.closed = true;
+        let waker = buffer.waker.take();
+        drop(buffer);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Stream of [`OwnedReconnectEvent`]s.
+///
+/// Set up by [`ReconnectOptions::with_event_channel`](crate::ReconnectOptions::with_event_channel)
+/// and taken with [`StubbornIo::take_event_stream`](super::StubbornIo::take_event_stream).
+///
+/// Ends once the `StubbornIo` (including its halves and any background
+/// reconnect task) has been dropped and the buffered events have been read.
+pub struct ReconnectEventStream {
+    shared: Shared,
+}
+
+impl ReconnectEventStream {
+    /// Total number of events dropped so far because the buffer was full.
+    #[must_use]
+    pub fn lagged(&self) -> u64 {
+        lock(&self.shared).lagged
+    }
+}
+
+impl Stream for ReconnectEventStream {
+    type Item = OwnedReconnectEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut buffer = lock(&self.shared);
+        if let Some(event) = buffer.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if buffer.closed {
+            return Poll::Ready(None);
+        }
+        buffer.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (lock(&self.shared).events.len(), None)
+    }
+}
@@ -0,0 +1,245 @@
+use super::io::{StubbornIo, UnderlyingIo};
+use futures_core::Stream;
+use futures_sink::Sink;
+use log::debug;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::bytes::{Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use tokio_util::io::poll_read_buf;
+
+const INITIAL_CAPACITY: usize = 8 * 1024;
+const BACKPRESSURE_BOUNDARY: usize = INITIAL_CAPACITY;
+
+/// A frame-atomic [`Sink`] + [`Stream`] over a [`StubbornIo`], driven by a
+/// `tokio_util` [`Encoder`]/[`Decoder`]. Requires the `codec` feature.
+///
+/// Unlike wrapping a `StubbornIo` in `tokio_util::codec::Framed`, this type
+/// knows when the underlying connection has been replaced:
+///
+/// * **Outbound:** a frame that was partially written when the connection
+///   dropped is restarted from its first byte on the new connection, so the
+///   peer never receives the tail of a frame whose head went to the old one.
+///   Frames fully accepted by the old connection are not re-sent (see
+///   [`WriteFailurePolicy::Replay`](crate::config::WriteFailurePolicy::Replay)
+///   for that; it replays the frames the old connection accepted in full, but
+///   not the head of the restarted one).
+/// * **Inbound:** bytes of a partially decoded frame from the old connection
+///   are discarded instead of being prefixed to the new connection's data,
+///   and the codec is reset to the copy passed to [`Self::new`] (decoders such
+///   as `LinesCodec` or `LengthDelimitedCodec` keep parse state about the
+///   discarded bytes). Complete frames already received are still delivered.
+///
+/// ```no_run
+/// use futures::{SinkExt, StreamExt};
+/// use sdre_stubborn_io::StubbornTcpStream;
+/// use sdre_stubborn_io::tokio::StubbornFramed;
+/// use tokio_util::codec::LinesCodec;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let stream = StubbornTcpStream::connect("127.0.0.1:30047".parse()?).await?;
+/// let mut lines = StubbornFramed::new(stream, LinesCodec::new());
+/// lines.send("{\"flight\":\"UAL123\"}".to_string()).await?;
+/// while let Some(line) = lines.next().await {
+///     println!("{}", line?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct StubbornFramed<T: UnderlyingIo, C> {
+    io: StubbornIo<T>,
+    codec: C,
+    /// Untouched copy of the codec as configured, restored on reconnect.
+    pristine: C,
+    read_buf: BytesMut,
+    /// Connection the contents of `read_buf` were read from.
+    read_generation: u64,
+    encode_buf: BytesMut,
+    /// Encoded frames not yet fully written, oldest first.
+    frames: VecDeque<Bytes>,
+    /// Total length of `frames`.
+    queued: usize,
+    /// Bytes of the front frame accepted by connection `write_generation`.
+    written: usize,
+    write_generation: u64,
+}
+
+impl<T: UnderlyingIo, C: Clone> StubbornFramed<T, C> {
+    /// Wraps a connected `io`, encoding and decoding frames with `codec`.
+    ///
+    /// `codec` is cloned up front; the clone replaces the live codec whenever a
+    /// reconnect invalidates its inbound state.
+    #[must_use]
+    pub fn new(io: StubbornIo<T>, codec: C) -> Self {
+        let generation = io.generation();
+        Self {
+            io,
+            pristine: codec.clone(),
+            codec,
+            read_buf: BytesMut::with_capacity(INITIAL_CAPACITY),
+            read_generation: generation,
+            encode_buf: BytesMut::new(),
+            frames: VecDeque::new(),
+            queued: 0,
+            written: 0,
+            write_generation: generation,
+        }
+    }
+}
+
+impl<T: UnderlyingIo, C> StubbornFramed<T, C> {
+    /// Returns a reference to the underlying stream.
+    #[must_use]
+    pub const fn get_ref(&self) -> &StubbornIo<T> {
+        &self.io
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// Reading or writing through it directly bypasses the frame bookkeeping.
+    pub const fn get_mut(&mut self) -> &mut StubbornIo<T> {
+        &mut self.io
+    }
+
+    /// Returns a reference to the codec.
+    #[must_use]
+    pub const fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Consumes the wrapper, returning the underlying stream. Buffered inbound
+    /// bytes and unsent frames are dropped.
+    #[must_use]
+    pub fn into_inner(self) -> StubbornIo<T> {
+        self.io
+    }
+}
+
+impl<T, C> StubbornFramed<T, C>
+where
+    T: UnderlyingIo + AsyncWrite,
+{
+    /// Writes queued frames until none are left, restarting the front frame
+    /// whenever the connection it was partially written to has been replaced.
+    fn poll_write_frames(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Some(frame) = self.frames.front() {
+            let generation = self.io.generation();
+            if generation != self.write_generation {
+                if self.written > 0 {
+                    debug!(
+                        "{}Restarting frame after reconnect ({} of {} byte(s) had been written)",
+                        self.io.get_connection_name(),
+                        self.written,
+                        frame.len()
+                    );
+                    self.io.discard_partial_write();
+                }
+                self.written = 0;
+                self.write_generation = generation;
+            }
+
+            let n = ready!(Pin::new(&mut self.io).poll_write(cx, &frame[self.written..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    ErrorKind::WriteZero,
+                    "failed to write frame to transport",
+                )));
+            }
+            self.written += n;
+            if self.written == frame.len() {
+                self.queued -= frame.len();
+                self.written = 0;
+                self.frames.pop_front();
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T, C> Stream for StubbornFramed<T, C>
+where
+    T: UnderlyingIo + AsyncRead,
+    C: Decoder + Clone + Unpin,
+{
+    type Item = Result<C::Item, C::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.codec.decode(&mut this.read_buf)? {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            // Whatever is left cannot be decoded on its own. If it came from a
+            // previous connection, its remainder is never going to arrive.
+            let generation = this.io.generation();
+            if generation != this.read_generation {
+                if !this.read_buf.is_empty() {
+                    debug!(
+                        "{}Discarding {} byte(s) of a partial frame after reconnect",
+                        this.io.get_connection_name(),
+                        this.read_buf.len()
+                    );
+                    this.read_buf.clear();
+                }
+                this.codec = this.pristine.clone();
+                this.read_generation = generation;
+            }
+
+            this.read_buf.reserve(1);
+            let n = ready!(poll_read_buf(
+                Pin::new(&mut this.io),
+                cx,
+                &mut this.read_buf
+            ))?;
+            if n == 0 {
+                // Only reachable for transports whose `is_final_read` does not
+                // treat an empty read as a disconnect.
+                return Poll::Ready(this.codec.decode_eof(&mut this.read_buf).transpose());
+            }
+        }
+    }
+}
+
+impl<T, C, I> Sink<I> for StubbornFramed<T, C>
+where
+    T: UnderlyingIo + AsyncWrite,
+    C: Encoder<I> + Unpin,
+{
+    type Error = C::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.queued >= BACKPRESSURE_BOUNDARY {
+            ready!(this.poll_write_frames(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.codec.encode(item, &mut this.encode_buf)?;
+        let frame = this.encode_buf.split().freeze();
+        if !frame.is_empty() {
+            this.queued += frame.len();
+            this.frames.push_back(frame);
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_frames(cx))?;
+        ready!(Pin::new(&mut this.io).poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        ready!(Pin::new(&mut self.io).poll_shutdown(cx))?;
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,84 @@
+//! Timer and bookkeeping backing [`ReconnectOptions::with_heartbeat`](crate::ReconnectOptions::with_heartbeat).
+
+use super::reconnect::deadline_after;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use tokio::time::{Sleep, sleep};
+
+/// Heartbeat state for one `StubbornIo`. The writes themselves are performed by
+/// `StubbornIo`, which owns the underlying stream.
+pub(super) struct Heartbeat {
+    pub(super) payload: Arc<[u8]>,
+    interval: Duration,
+    timer: Pin<Box<Sleep>>,
+    /// Bytes of the current heartbeat already written; `None` when no heartbeat
+    /// is in flight. Once started, a heartbeat is completed before any caller
+    /// write is let through.
+    pub(super) in_flight: Option<usize>,
+    /// A heartbeat was completed by `poll_heartbeat` but not yet flushed.
+    pub(super) flush_pending: bool,
+    /// The caller's most recent write was short, so it is most likely in the
+    /// middle of a larger message; heartbeats wait until it completes one.
+    caller_mid_write: bool,
+    /// Waker of a `poll_heartbeat` that found the timer due but had to defer.
+    deferred: Option<Waker>,
+}
+
+impl Heartbeat {
+    pub(super) fn new(interval: Duration, payload: Arc<[u8]>) -> Self {
+        Self {
+            payload,
+            interval,
+            timer: Box::pin(sleep(interval)),
+            in_flight: None,
+            flush_pending: false,
+            caller_mid_write: false,
+            deferred: None,
+        }
+    }
+
+    /// Restarts the inactivity window.
+    pub(super) fn reset(&mut self) {
+        self.timer.as_mut().reset(deadline_after(self.interval));
+    }
+
+    /// A new connection replaced the old one: any partially written heartbeat
+    /// belongs to the old connection and is abandoned.
+    pub(super) fn on_reconnect(&mut self) {
+        self.in_flight = None;
+        self.flush_pending = false;
+        self.reset();
+    }
+
+    /// Accounts for a caller write that accepted `written` of `requested` bytes.
+    pub(super) fn on_caller_write(&mut self, written: usize, requested: usize) {
+        if written == 0 {
+            return;
+        }
+        self.reset();
+        let mid_write = written < requested;
+        if self.caller_mid_write
+            && !mid_write
+            && let Some(waker) = self.deferred.take()
+        {
+            waker.wake();
+        }
+        self.caller_mid_write = mid_write;
+    }
+
+    /// Ready once a heartbeat should be started: the inactivity window elapsed
+    /// and the caller is not in the middle of a message.
+    pub(super) fn poll_due(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.timer.as_mut().poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        if self.caller_mid_write {
+            self.deferred = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        Poll::Ready(())
+    }
+}
@@ -1,34 +1,23 @@
-use crate::config::{ReconnectEvent, ReconnectOptions, WriteFailurePolicy, format_log_prefix};
-use log::{error, info, warn};
-use std::future::Future;
+use super::events::{self, ReconnectEventStream};
+use super::heartbeat::Heartbeat;
+use super::reconnect::{Reconnect, ReconnectPlan, SharedContext, deadline_after, lock, set_state};
+use super::replay::ReplayBuffer;
+use super::stats::{ConnectionStats, StatsSnapshot};
+use crate::config::{
+    DisconnectReason, DurationIterator, EventListenerHandle, ReconnectEvent, ReconnectOptions,
+    WriteFailurePolicy, format_log_prefix,
+};
+use log::{debug, error, info, warn};
+use std::future::{Future, poll_fn};
 use std::io::{self, ErrorKind, IoSlice};
-use std::marker::PhantomData;
+use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
-use std::sync::Arc;
-use std::task::{Context, Poll};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker, ready};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
-use tokio::time::{sleep, timeout};
-
-/// Run `establish` with the optional per-attempt timeout from `ReconnectOptions`.
-/// Elapsed timeouts surface as `io::ErrorKind::TimedOut` so the reconnect machinery
-/// treats them as a failed attempt and proceeds to the next backoff step.
-async fn establish_with_timeout<T: UnderlyingIo>(
-    ctx: T::Context,
-    deadline: Option<Duration>,
-) -> io::Result<T> {
-    if let Some(d) = deadline {
-        timeout(d, T::establish(ctx)).await.unwrap_or_else(|_| {
-            Err(io::Error::new(
-                ErrorKind::TimedOut,
-                "connect attempt exceeded configured connect_timeout",
-            ))
-        })
-    } else {
-        T::establish(ctx).await
-    }
-}
+use tokio::sync::watch;
+use tokio::time::{Instant, Sleep, sleep};
 
 /// Trait that should be implemented for an [`AsyncRead`] and/or [`AsyncWrite`]
 /// item to enable it to work with the [`StubbornIo`] struct.
@@ -37,7 +26,10 @@ async fn establish_with_timeout<T: UnderlyingIo>(
 /// caller-supplied value handed to [`establish`](UnderlyingIo::establish) on every
 /// (re)connect. Implementers that need no context should set `type Context = ();`
 /// explicitly; no default is provided.
-pub trait UnderlyingIo: Sized + Unpin {
+///
+/// Implementers must be `'static`: the connect sequence that produces a
+/// connection is boxed and stored in the stream while it runs.
+pub trait UnderlyingIo: Sized + Unpin + 'static {
     /// The caller-supplied value passed to [`Self::establish`] for every
     /// (re)connect attempt. Must be cloneable so it can be reused across attempts.
     type Context: Clone + Send + Unpin + 'static;
@@ -90,34 +82,42 @@ pub trait UnderlyingIo: Sized + Unpin {
         // indicative of EOF hit
         bytes_read == 0
     }
-}
 
-struct AttemptsTracker {
-    attempt_num: usize,
-    retries_remaining: Box<dyn Iterator<Item = Duration> + Send>,
-}
+    /// The remote address this item ended up connected to, for transports that
+    /// pick one themselves inside [`Self::establish`] (e.g. by resolving a host
+    /// name). When `Some`, it is reported through
+    /// [`ReconnectEvent::AddressResolved`] after every successful attempt.
+    /// `ctx` is the context the attempt connected with.
+    ///
+    /// Defaults to `None`; transports whose context already *is* the address have
+    /// nothing to add.
+    fn resolved_addr(&self, _ctx: &Self::Context) -> Option<SocketAddr> {
+        None
+    }
 
-struct ReconnectStatus<T: UnderlyingIo> {
-    attempts_tracker: AttemptsTracker,
-    /// `None` while no reconnect has been scheduled yet; replaced by `on_disconnect`
-    /// before any poll on this status occurs.
-    reconnect_attempt: Option<Pin<Box<dyn Future<Output = io::Result<T>> + Send>>>,
-    _phantom_data: PhantomData<T::Context>,
-}
+    /// Classifies an error returned by [`Self::establish`]. A fatal error — one
+    /// that retrying cannot fix, such as a TLS certificate that fails validation —
+    /// stops the reconnect machinery immediately: the attempt is reported through
+    /// `ConnectFailed`, followed by `Exhausted` (with
+    /// [`ExhaustedReason::FatalError`](crate::config::ExhaustedReason::FatalError)),
+    /// and the stream becomes terminal.
+    ///
+    /// Defaults to `false`: every establish error is retried according to the
+    /// retries iterator.
+    #[must_use]
+    fn is_fatal_connect_error(_err: &io::Error) -> bool {
+        false
+    }
 
-impl<T> ReconnectStatus<T>
-where
-    T: UnderlyingIo,
-{
-    pub(crate) fn new(options: &ReconnectOptions) -> Self {
-        Self {
-            attempts_tracker: AttemptsTracker {
-                attempt_num: 0,
-                retries_remaining: (options.retries_to_attempt_fn)(),
-            },
-            reconnect_attempt: None,
-            _phantom_data: PhantomData,
-        }
+    /// A human-readable description of `ctx` for diagnostics: with the
+    /// `tracing` feature, it is recorded as the `context` field of the
+    /// stream's span.
+    ///
+    /// Defaults to `None`, since `Context` is not required to implement
+    /// `Debug`. The built-in transports return the `Debug` form of theirs.
+    #[must_use]
+    fn describe_context(_ctx: &Self::Context) -> Option<String> {
+        None
     }
 }
 
@@ -125,18 +125,48 @@ where
 /// invoke the [`UnderlyingIo::establish`] upon initialization and when a reconnect is needed.
 ///
 /// Because it implements deref, you are able to invoke all of the original methods on the wrapped IO.
+/// Dereferencing a stream from [`StubbornIo::connect_lazy`] before its first
+/// connection has been established panics; [`StubbornIo::get_ref`] and
+/// [`StubbornIo::get_mut`] are the non-panicking alternatives.
 pub struct StubbornIo<T: UnderlyingIo> {
     status: Status<T>,
-    underlying_io: T,
-    options: ReconnectOptions,
-    ctor_arg: T::Context,
+    /// `None` until the first connection is established; afterwards the most
+    /// recent connection, which is live whenever `status` is `Connected`.
+    underlying_io: Option<T>,
+    options: ReconnectOptions<T>,
+    ctor_arg: SharedContext<T::Context>,
     /// Pre-formatted log prefix (e.g. `StubbornIo(foo): `), cached once at construction.
     log_prefix: Arc<str>,
+    /// Retransmit history; `Some` only under [`WriteFailurePolicy::Replay`].
+    replay: Option<ReplayBuffer>,
+    /// Deadline for the next received bytes; `Some` only when
+    /// [`ReconnectOptions::with_read_idle_timeout`] is set. Re-armed on every
+    /// successful read and every reconnect.
+    idle_timer: Option<Pin<Box<Sleep>>>,
+    /// `Some` only when [`ReconnectOptions::with_heartbeat`] is set.
+    heartbeat: Option<Heartbeat>,
+    /// Published to [`StubbornIo::state_watcher`] receivers.
+    state: watch::Sender<ConnectionState>,
+    /// Incremented every time an underlying connection is installed, so
+    /// wrappers can tell which connection buffered state belongs to.
+    generation: u64,
+    /// Shared with reconnect sequences and [`StubbornIo::stats_handle`] callers.
+    stats: Arc<ConnectionStats>,
+    /// Span the stream's operations and reconnect attempts run in.
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    /// Set up by [`ReconnectOptions::with_event_channel`]; `None` once taken.
+    event_stream: Option<ReconnectEventStream>,
+    /// When the current (or most recent) connection was established.
+    connected_at: Option<Instant>,
+    /// The retries the last connect sequence did not use; kept only under
+    /// [`ReconnectOptions::with_stability_window`].
+    retries: Option<DurationIterator>,
 }
 
 enum Status<T: UnderlyingIo> {
     Connected,
-    Disconnected(ReconnectStatus<T>),
+    Disconnected(Reconnect<T>),
     FailedAndExhausted,
     /// Terminal state entered after a successful (or errored) `poll_shutdown`.
     /// No further reconnects will be attempted; subsequent reads/writes/shutdowns
@@ -144,6 +174,40 @@ enum Status<T: UnderlyingIo> {
     Closed,
 }
 
+/// Connection state of a [`StubbornIo`], as published through
+/// [`StubbornIo::state_watcher`].
+///
+/// Non-exhaustive so new states can be added without breaking existing matches.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// A connection is established.
+    Connected,
+    /// Not connected; connect attempt `attempt` (0 = initial connect) starts at
+    /// `next_at`, or is already under way if that is in the past.
+    Reconnecting {
+        /// Same counter as [`ReconnectEvent::Connected::attempt`].
+        attempt: usize,
+        /// When the attempt starts.
+        next_at: Instant,
+    },
+    /// The stream gave up reconnecting; it will not connect again.
+    Exhausted,
+    /// The stream was shut down.
+    Closed,
+}
+
+/// The error a [`RetryPolicy`](crate::config::RetryPolicy) sees for a lost
+/// connection; `None` for reconnects the caller asked for.
+fn disconnect_error(reason: DisconnectReason) -> Option<io::Error> {
+    match reason {
+        DisconnectReason::IoError(kind) => Some(io::Error::from(kind)),
+        DisconnectReason::EndOfStream => Some(io::Error::from(ErrorKind::UnexpectedEof)),
+        DisconnectReason::IdleTimeout => Some(io::Error::from(ErrorKind::TimedOut)),
+        DisconnectReason::Forced | DisconnectReason::ContextChanged => None,
+    }
+}
+
 #[inline]
 fn poll_err<T>(
     kind: ErrorKind,
@@ -167,17 +231,46 @@ fn closed_err<T>() -> Poll<io::Result<T>> {
     )
 }
 
+/// The single buffer of a one-element write, as built by the non-vectored write
+/// paths before calling [`StubbornIo::poll_write_with`].
+pub(super) fn first_slice<'a>(bufs: &'a [IoSlice<'_>]) -> &'a [u8] {
+    bufs.first().map_or(&[], |buf| buf)
+}
+
+fn write_vectored<T: AsyncWrite + Unpin>(
+    io: &mut T,
+    cx: &mut Context<'_>,
+    bufs: &[IoSlice<'_>],
+) -> Poll<io::Result<usize>> {
+    AsyncWrite::poll_write_vectored(Pin::new(io), cx, bufs)
+}
+
+const NEVER_CONNECTED: &str = "StubbornIo has not established its first connection yet";
+
+/// The connection of a `Connected` stream.
+const fn live_io<T>(underlying_io: &mut Option<T>) -> &mut T {
+    underlying_io.as_mut().expect(NEVER_CONNECTED)
+}
+
+/// # Panics
+///
+/// Panics if the stream was created by [`StubbornIo::connect_lazy`] and has not
+/// connected yet. Use [`StubbornIo::get_ref`] when that is possible.
 impl<T: UnderlyingIo> Deref for StubbornIo<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.underlying_io
+        self.underlying_io.as_ref().expect(NEVER_CONNECTED)
     }
 }
 
+/// # Panics
+///
+/// Panics if the stream was created by [`StubbornIo::connect_lazy`] and has not
+/// connected yet. Use [`StubbornIo::get_mut`] when that is possible.
 impl<T: UnderlyingIo> DerefMut for StubbornIo<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.underlying_io
+        live_io(&mut self.underlying_io)
     }
 }
 
@@ -204,6 +297,89 @@ where
         self.options.write_failure_policy
     }
 
+    /// The name set with [`ReconnectOptions::with_connection_name`], unformatted.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub(crate) const fn connection_name(&self) -> &Arc<str> {
+        &self.options.connection_name
+    }
+
+    /// Identifies the current underlying connection: 0 before the first one,
+    /// incremented every time a connection is installed.
+    pub(crate) const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Tells the stream that the caller will rewrite its unfinished message from
+    /// the first byte on the new connection, so [`WriteFailurePolicy::Replay`]
+    /// must neither replay the part the old connection accepted nor treat the
+    /// rewrite as its continuation.
+    #[cfg_attr(not(feature = "codec"), allow(dead_code))]
+    pub(crate) fn discard_partial_write(&mut self) {
+        if let Some(replay) = &mut self.replay {
+            replay.discard_partial();
+        }
+    }
+
+    /// Returns a receiver that always holds the stream's current
+    /// [`ConnectionState`], for tasks that want to `changed().await` on
+    /// connection changes (to update a health endpoint, pause producers, ...)
+    /// rather than react inside the synchronous event callback.
+    ///
+    /// With background reconnects the state moves to `Connected` as soon as the
+    /// new connection is up, before the stream itself is polled again. Once
+    /// the stream is dropped, `changed()` returns an error.
+    #[must_use]
+    pub fn state_watcher(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Takes the stream of owned events set up by
+    /// [`ReconnectOptions::with_event_channel`]. Returns `None` if the options
+    /// did not enable it, or if it has already been taken.
+    pub const fn take_event_stream(&mut self) -> Option<ReconnectEventStream> {
+        self.event_stream.take()
+    }
+
+    /// Adds an event listener after construction; see
+    /// [`ReconnectOptions::add_event_listener`].
+    pub fn add_event_listener(
+        &self,
+        listener: impl for<'a> Fn(ReconnectEvent<'a>) + Send + Sync + 'static,
+    ) -> EventListenerHandle {
+        self.options.events.add(Arc::new(listener))
+    }
+
+    /// Returns the stream's current statistics: reconnects, failed attempts,
+    /// bytes moved and dropped, up- and downtime, and outage durations.
+    #[must_use]
+    pub fn stats(&self) -> StatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    /// Returns a shared handle to the live statistics, for a metrics exporter
+    /// that samples them without access to the stream. It stays readable after
+    /// the stream has been dropped.
+    #[must_use]
+    pub fn stats_handle(&self) -> Arc<ConnectionStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// The current (or most recent) connection, or `None` if the stream was
+    /// created by [`Self::connect_lazy`] and has not connected yet.
+    ///
+    /// This takes precedence over a `get_ref` of `T` reached through `Deref`
+    /// (such as the TLS stream's); call that one on the returned reference.
+    #[must_use]
+    pub const fn get_ref(&self) -> Option<&T> {
+        self.underlying_io.as_ref()
+    }
+
+    /// Mutable form of [`Self::get_ref`].
+    #[must_use]
+    pub const fn get_mut(&mut self) -> Option<&mut T> {
+        self.underlying_io.as_mut()
+    }
+
     /// Returns `true` if the stream is currently connected and ready for I/O.
     #[must_use]
     pub const fn is_connected(&self) -> bool {
@@ -225,225 +401,375 @@ where
         matches!(self.status, Status::Closed)
     }
 
+    /// Drops the current connection and reconnects as if it had been lost,
+    /// for when the application knows it is bad before the socket does.
+    ///
+    /// Emits [`ReconnectEvent::Disconnected`] with
+    /// [`DisconnectReason::Forced`] and schedules the first attempt from the
+    /// retries iterator; attempts honour the connect timeout as usual. The next
+    /// read or write drives the reconnect. Does nothing unless
+    /// [`Self::is_connected`].
+    pub fn force_reconnect(&mut self) {
+        Pin::new(self).force_reconnect_pinned(&Context::from_waker(Waker::noop()));
+    }
+
+    /// [`Self::force_reconnect`] for poll-based callers: `cx` is woken so the
+    /// task polls the stream again and drives the reconnect.
+    pub fn force_reconnect_pinned(self: Pin<&mut Self>, cx: &Context<'_>) {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
+        if self.is_connected() {
+            info!("{}Reconnect requested", self.log_prefix);
+            self.on_disconnect(cx, DisconnectReason::Forced);
+        }
+    }
+
+    /// Returns a copy of the context passed to [`UnderlyingIo::establish`] on
+    /// the next (re)connect attempt.
+    #[must_use]
+    pub fn context(&self) -> T::Context {
+        lock(&self.ctor_arg).clone()
+    }
+
+    /// Replaces the context used for every future (re)connect attempt, e.g.
+    /// after an operator changed the upstream address. The current connection
+    /// is left alone; an attempt that is already under way still uses the old
+    /// context. Emits [`ReconnectEvent::ContextChanged`].
+    pub fn set_context(&mut self, ctx: T::Context) {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
+        info!("{}Connection context replaced", self.log_prefix);
+        #[cfg(feature = "tracing")]
+        if let Some(description) = T::describe_context(&ctx) {
+            self.span.record("context", description.as_str());
+        }
+        *lock(&self.ctor_arg) = ctx;
+        self.options.events.emit(ReconnectEvent::ContextChanged);
+    }
+
+    /// [`Self::set_context`], then moves over to the new context right away: a
+    /// connected stream is dropped as by [`Self::force_reconnect`], with
+    /// [`DisconnectReason::ContextChanged`].
+    pub fn retarget(&mut self, ctx: T::Context) {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
+        self.set_context(ctx);
+        if self.is_connected() {
+            Pin::new(self).on_disconnect(
+                &Context::from_waker(Waker::noop()),
+                DisconnectReason::ContextChanged,
+            );
+        }
+    }
+
     /// Connects (or attempts to reconnect) using the supplied [`ReconnectOptions`].
+    ///
+    /// Resolves once the initial connection is established, retrying according
+    /// to the retries iterator, or with the last connect error once the stream
+    /// gives up. See [`Self::connect_lazy`] for a constructor that does not wait.
     pub async fn connect_with_options(
         ctor_arg: T::Context,
-        options: ReconnectOptions,
+        options: impl Into<ReconnectOptions<T>>,
     ) -> io::Result<Self> {
-        let log_prefix = format_log_prefix(&options.connection_name);
-        let emit = |ev: ReconnectEvent<'_>| (options.event_callback)(ev);
-
-        let tcp = match establish_with_timeout::<T>(ctor_arg.clone(), options.connect_timeout).await
-        {
-            Ok(tcp) => {
-                info!("{log_prefix}Initial connection succeeded.");
-                emit(ReconnectEvent::Connected { attempt: 0 });
-                tcp
-            }
-            Err(e) => {
-                warn!("{log_prefix}Initial connection failed due to: {e:?}.");
-                emit(ReconnectEvent::ConnectFailed {
-                    error: &e,
-                    attempt: 0,
-                });
-
-                if options.exit_if_first_connect_fails {
-                    error!("{log_prefix}Bailing after initial connection failure.");
-                    return Err(e);
-                }
-
-                let mut result = Err(e);
-
-                for (i, duration) in (options.retries_to_attempt_fn)().enumerate() {
-                    let reconnect_num = i + 1;
-
-                    emit(ReconnectEvent::ReconnectScheduled {
-                        attempt: reconnect_num,
-                        delay: duration,
-                    });
-                    warn!(
-                        "{log_prefix}Will re-perform initial connect attempt #{reconnect_num} in {duration:?}."
-                    );
-
-                    sleep(duration).await;
-
-                    info!("{log_prefix}Attempting reconnect #{reconnect_num} now.");
-
-                    match establish_with_timeout::<T>(ctor_arg.clone(), options.connect_timeout)
-                        .await
-                    {
-                        Ok(tcp) => {
-                            emit(ReconnectEvent::Connected {
-                                attempt: reconnect_num,
-                            });
-                            info!("{log_prefix}Initial connection successfully established.");
-                            result = Ok(tcp);
-                            break;
-                        }
-                        Err(e) => {
-                            emit(ReconnectEvent::ConnectFailed {
-                                error: &e,
-                                attempt: reconnect_num,
-                            });
-                            result = Err(e);
-                        }
-                    }
-                }
+        let mut stream = Self::connect_lazy(ctor_arg, options);
+        poll_fn(|cx| Pin::new(&mut stream).poll_established(cx)).await?;
+        Ok(stream)
+    }
 
-                match result {
-                    Ok(tcp) => tcp,
-                    Err(e) => {
-                        emit(ReconnectEvent::Exhausted);
-                        error!(
-                            "{log_prefix}No more re-connect retries remaining. Never able to establish initial connection."
-                        );
-                        return Err(e);
-                    }
-                }
-            }
+    /// Creates the stream without waiting for a connection: it starts out
+    /// disconnected, with the initial connect attempt scheduled to run right
+    /// away. The first read or write drives it, and any later attempts, exactly
+    /// like a reconnect, so [`WriteFailurePolicy`] applies to writes issued
+    /// before the connection is up. Events are the same as for
+    /// [`Self::connect_with_options`].
+    ///
+    /// Must be called from within a Tokio runtime. Dereferencing the stream
+    /// before the first connection is established panics; check
+    /// [`Self::is_connected`] first.
+    pub fn connect_lazy(ctor_arg: T::Context, options: impl Into<ReconnectOptions<T>>) -> Self {
+        let options = options.into();
+        let event_stream = options.event_channel.map(|capacity| {
+            let (sender, stream) = events::channel(capacity, Arc::clone(&options.connection_name));
+            options
+                .events
+                .add(Arc::new(move |event| sender.send(&event)));
+            stream
+        });
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "stubborn_io",
+            connection_name = %options.connection_name,
+            context = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        if let Some(ctx) = T::describe_context(&ctor_arg) {
+            span.record("context", ctx.as_str());
+        }
+        let log_prefix = format_log_prefix(&options.connection_name);
+        let idle_timer = options.read_idle_timeout.map(|idle| Box::pin(sleep(idle)));
+        let heartbeat = options
+            .heartbeat
+            .as_ref()
+            .map(|(interval, payload)| Heartbeat::new(*interval, Arc::clone(payload)));
+        let replay = match options.write_failure_policy {
+            WriteFailurePolicy::Replay { capacity } => Some(ReplayBuffer::new(capacity)),
+            WriteFailurePolicy::Backpressure | WriteFailurePolicy::DropAndNotify => None,
         };
 
-        Ok(Self {
-            status: Status::Connected,
-            ctor_arg,
-            underlying_io: tcp,
+        let (state, _) = watch::channel(ConnectionState::Reconnecting {
+            attempt: 0,
+            next_at: Instant::now(),
+        });
+        let mut stream = Self {
+            // Replaced below, once the stream exists to plan the initial connect.
+            status: Status::Closed,
+            ctor_arg: Arc::new(Mutex::new(ctor_arg)),
+            underlying_io: None,
             options,
             log_prefix,
-        })
+            replay,
+            idle_timer,
+            heartbeat,
+            state,
+            generation: 0,
+            stats: Arc::new(ConnectionStats::new()),
+            #[cfg(feature = "tracing")]
+            span,
+            event_stream,
+            connected_at: None,
+            retries: None,
+        };
+        stream.status = Status::Disconnected(stream.start_connecting(None));
+        stream
     }
 
-    fn on_disconnect(mut self: Pin<&mut Self>, cx: &Context<'_>) {
-        let prefix = Arc::clone(&self.log_prefix);
-        match &mut self.status {
-            // initial disconnect
-            Status::Connected => {
-                error!("{prefix}Disconnect occurred");
-                (self.options.event_callback)(ReconnectEvent::Disconnected);
-                self.status = Status::Disconnected(ReconnectStatus::new(&self.options));
-            }
-            // Already disconnected; a previous reconnect attempt failed. The
-            // ConnectFailed event was emitted at the call site (poll_disconnect)
-            // where the error is in scope. No additional emit here.
-            Status::Disconnected(_) => {}
-            Status::FailedAndExhausted | Status::Closed => {
-                unreachable!("{prefix}on_disconnect will not occur for already-terminal state.")
-            }
-        }
-
-        let ctor_arg = self.ctor_arg.clone();
-        let connect_timeout = self.options.connect_timeout;
-
-        // this is ensured to be true now
-        if let Status::Disconnected(reconnect_status) = &mut self.status {
-            let Some(next_duration) = reconnect_status.attempts_tracker.retries_remaining.next()
-            else {
-                error!("{prefix}No more re-connect retries remaining. Giving up.");
-                (self.options.event_callback)(ReconnectEvent::Exhausted);
-                self.status = Status::FailedAndExhausted;
-                cx.waker().wake_by_ref();
-                return;
-            };
-
-            let future_instant = sleep(next_duration);
-
-            reconnect_status.attempts_tracker.attempt_num += 1;
-            let cur_num = reconnect_status.attempts_tracker.attempt_num;
-            let log_prefix = Arc::clone(&prefix);
-
-            let reconnect_attempt = async move {
-                future_instant.await;
-                info!("{log_prefix}Attempting reconnect #{cur_num} now.");
-                establish_with_timeout::<T>(ctor_arg, connect_timeout).await
-            };
+    /// Starts a connect sequence (inline or in the background, per the
+    /// options): the initial connect if `lost` is `None`, otherwise a
+    /// reconnect after losing the connection for that reason.
+    fn start_connecting(&mut self, lost: Option<DisconnectReason>) -> Reconnect<T> {
+        let plan = ReconnectPlan {
+            ctx: Arc::clone(&self.ctor_arg),
+            connect_timeout: self.options.connect_timeout,
+            on_established: self.options.on_established.clone(),
+            events: Arc::clone(&self.options.events),
+            state: self.state.clone(),
+            stats: Arc::clone(&self.stats),
+            retry_policy: self.options.retry_policy.clone(),
+            deadline: self
+                .options
+                .max_disconnected_duration
+                .and_then(|max| Instant::now().checked_add(max)),
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
+            log_prefix: Arc::clone(&self.log_prefix),
+            initial: lost.is_none(),
+            exit_if_first_connect_fails: self.options.exit_if_first_connect_fails,
+        };
+        let retries = self.next_retries();
+        let error = lost.and_then(disconnect_error);
+        Reconnect::start(plan, retries, error.as_ref(), self.options.spawn_reconnect)
+    }
 
-            reconnect_status.reconnect_attempt = Some(Box::pin(reconnect_attempt));
+    /// The retries left over from the last connect sequence if the connection
+    /// it established did not last the stability window, otherwise a fresh
+    /// iterator.
+    fn next_retries(&mut self) -> DurationIterator {
+        let unstable = self
+            .options
+            .stability_window
+            .zip(self.connected_at)
+            .is_some_and(|(window, since)| since.elapsed() < window);
+        match self.retries.take() {
+            Some(rest) if unstable => rest,
+            _ => (self.options.retries_to_attempt_fn)(),
+        }
+    }
 
-            info!("{prefix}Will perform reconnect attempt #{cur_num} in {next_duration:?}.");
-            (self.options.event_callback)(ReconnectEvent::ReconnectScheduled {
-                attempt: cur_num,
-                delay: next_duration,
-            });
+    /// Enters the stream's span until the returned guard is dropped.
+    #[cfg(feature = "tracing")]
+    fn enter_span(&self) -> tracing::span::EnteredSpan {
+        self.span.clone().entered()
+    }
 
-            cx.waker().wake_by_ref();
+    /// Drives a stream that has not connected yet until it either connects or
+    /// gives up, for [`Self::connect_with_options`].
+    fn poll_established(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
+        let terminal_err = match self.status {
+            Status::Disconnected(_) => self.as_mut().poll_disconnect(cx),
+            _ => None,
+        };
+        match self.status {
+            Status::Connected => Poll::Ready(Ok(())),
+            Status::Disconnected(_) => Poll::Pending,
+            Status::FailedAndExhausted => {
+                terminal_err.map_or_else(exhausted_err, |err| Poll::Ready(Err(err)))
+            }
+            Status::Closed => closed_err(),
         }
     }
 
-    fn poll_disconnect(mut self: Pin<&mut Self>, cx: &mut Context<'_>) {
+    /// Moves a connected stream to `Disconnected`, emitting
+    /// [`ReconnectEvent::Disconnected`], and starts reconnecting.
+    fn on_disconnect(mut self: Pin<&mut Self>, cx: &Context<'_>, reason: DisconnectReason) {
         let prefix = Arc::clone(&self.log_prefix);
-        let (attempt, attempt_num) = match &mut self.status {
-            Status::Connected | Status::FailedAndExhausted | Status::Closed => unreachable!(),
-            Status::Disconnected(status) => {
-                let Some(fut) = status.reconnect_attempt.as_mut() else {
-                    // No attempt scheduled yet; on_disconnect will populate it.
-                    return;
-                };
-                (Pin::new(fut), status.attempts_tracker.attempt_num)
+        match &mut self.status {
+            Status::Connected => {
+                #[cfg(feature = "tracing")]
+                if let DisconnectReason::IoError(kind) = reason {
+                    tracing::error!(error.kind = ?kind, "Disconnect occurred");
+                } else {
+                    tracing::error!(?reason, "Disconnect occurred");
+                }
+                error!("{prefix}Disconnect occurred: {reason:?}");
+                self.stats.on_disconnected(match reason {
+                    DisconnectReason::IoError(kind) => Some(kind),
+                    _ => None,
+                });
+                self.options
+                    .events
+                    .emit(ReconnectEvent::Disconnected { reason });
+                self.status = Status::Disconnected(self.start_connecting(Some(reason)));
+                cx.waker().wake_by_ref();
             }
+            Status::Disconnected(_) | Status::FailedAndExhausted | Status::Closed => {
+                unreachable!("{prefix}on_disconnect only occurs for a connected stream.")
+            }
+        }
+    }
+
+    /// Polls the running connect sequence, installing the new connection once
+    /// it succeeds. Returns the last attempt's error if the stream gave up.
+    fn poll_disconnect(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<io::Error> {
+        let Status::Disconnected(reconnect) = &mut self.status else {
+            unreachable!(
+                "{}poll_disconnect only occurs while disconnected.",
+                self.log_prefix
+            )
         };
 
-        match attempt.poll(cx) {
-            Poll::Ready(Ok(underlying_io)) => {
-                info!("{prefix}Connection re-established");
+        match Pin::new(reconnect).poll(cx) {
+            Poll::Ready(Ok((underlying_io, rest, since))) => {
                 cx.waker().wake_by_ref();
                 self.status = Status::Connected;
-                (self.options.event_callback)(ReconnectEvent::Connected {
-                    attempt: attempt_num,
-                });
-                self.underlying_io = underlying_io;
+                self.underlying_io = Some(underlying_io);
+                // When the connection was established, not when this poll
+                // picked it up (later, for a background reconnect).
+                self.connected_at = Some(since);
+                if self.options.stability_window.is_some() {
+                    self.retries = Some(rest);
+                }
+                self.generation += 1;
+                self.reset_idle_timer();
+                if let Some(heartbeat) = &mut self.heartbeat {
+                    heartbeat.on_reconnect();
+                }
+                if let Some(replay) = &mut self.replay {
+                    replay.arm();
+                }
+                None
             }
             Poll::Ready(Err(err)) => {
-                warn!("{prefix}Connection attempt #{attempt_num} failed: {err:?}");
-                (self.options.event_callback)(ReconnectEvent::ConnectFailed {
-                    error: &err,
-                    attempt: attempt_num,
-                });
-                self.on_disconnect(cx);
+                self.status = Status::FailedAndExhausted;
+                set_state(&self.state, ConnectionState::Exhausted);
+                cx.waker().wake_by_ref();
+                Some(err)
             }
-            Poll::Pending => {}
+            Poll::Pending => None,
+        }
+    }
+
+    fn reset_idle_timer(&mut self) {
+        if let (Some(timer), Some(idle)) = (&mut self.idle_timer, self.options.read_idle_timeout) {
+            timer.as_mut().reset(deadline_after(idle));
+        }
+    }
+
+    /// Checks the read idle deadline while a read is pending. On expiry the
+    /// connection is handed to `on_disconnect` and `true` is returned.
+    fn poll_idle_timeout(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        let Some(timer) = &mut self.idle_timer else {
+            return false;
+        };
+        if timer.as_mut().poll(cx).is_pending() {
+            return false;
         }
+        warn!(
+            "{}No data received for {:?}. Treating the connection as dead",
+            self.log_prefix,
+            self.options.read_idle_timeout.unwrap_or_default()
+        );
+        self.options.events.emit(ReconnectEvent::IdleTimeout);
+        self.on_disconnect(cx, DisconnectReason::IdleTimeout);
+        true
     }
 
-    fn is_read_disconnect_detected(
+    fn disconnect_reason(&self, err: &io::Error) -> Option<DisconnectReason> {
+        self.is_disconnect_error(err)
+            .then(|| DisconnectReason::IoError(err.kind()))
+    }
+
+    fn read_disconnect_reason(
         &self,
         poll_result: &Poll<io::Result<()>>,
         bytes_read: usize,
-    ) -> bool {
+    ) -> Option<DisconnectReason> {
         match poll_result {
-            Poll::Ready(Ok(())) if self.is_final_read(bytes_read) => true,
-            Poll::Ready(Err(err)) => self.is_disconnect_error(err),
-            _ => false,
+            Poll::Ready(Ok(())) if self.is_final_read(bytes_read) => {
+                Some(DisconnectReason::EndOfStream)
+            }
+            Poll::Ready(Err(err)) => self.disconnect_reason(err),
+            _ => None,
         }
     }
 
-    fn is_write_disconnect_detected<X>(&self, poll_result: &Poll<io::Result<X>>) -> bool {
+    fn write_disconnect_reason<X>(
+        &self,
+        poll_result: &Poll<io::Result<X>>,
+    ) -> Option<DisconnectReason> {
         match poll_result {
-            Poll::Ready(Err(err)) => self.is_disconnect_error(err),
-            _ => false,
+            Poll::Ready(Err(err)) => self.disconnect_reason(err),
+            _ => None,
         }
     }
-}
 
-impl<T> AsyncRead for StubbornIo<T>
-where
-    T: UnderlyingIo + AsyncRead,
-{
-    fn poll_read(
+    /// Shared read path for every read-like operation on the wrapped IO
+    /// (`AsyncRead::poll_read`, datagram `poll_recv`, ...).
+    ///
+    /// `read` performs the actual poll against the underlying item; disconnect
+    /// detection, reconnect scheduling and terminal-state handling live here so
+    /// that every transport goes through the same state machine.
+    pub(crate) fn poll_read_with<F>(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &mut ReadBuf<'_>,
-    ) -> Poll<io::Result<()>> {
+        read: F,
+    ) -> Poll<io::Result<()>>
+    where
+        F: FnOnce(&mut T, &mut Context<'_>, &mut ReadBuf<'_>) -> Poll<io::Result<()>>,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
         match &mut self.status {
             Status::Connected => {
                 let pre_len = buf.filled().len();
-                let poll = AsyncRead::poll_read(Pin::new(&mut self.underlying_io), cx, buf);
+                let poll = read(live_io(&mut self.underlying_io), cx, buf);
                 let post_len = buf.filled().len();
                 let bytes_read = post_len - pre_len;
-                if self.is_read_disconnect_detected(&poll, bytes_read) {
-                    self.on_disconnect(cx);
-                    Poll::Pending
-                } else {
-                    poll
+                if let Some(reason) = self.read_disconnect_reason(&poll, bytes_read) {
+                    self.on_disconnect(cx, reason);
+                    return Poll::Pending;
+                }
+                match poll {
+                    Poll::Ready(Ok(())) => {
+                        self.stats.add_read(bytes_read);
+                        self.reset_idle_timer();
+                    }
+                    Poll::Pending if self.as_mut().poll_idle_timeout(cx) => return Poll::Pending,
+                    Poll::Ready(Err(_)) | Poll::Pending => {}
                 }
+                poll
             }
             Status::Disconnected(_) => {
                 self.poll_disconnect(cx);
@@ -453,90 +779,323 @@ where
             Status::Closed => closed_err(),
         }
     }
-}
 
-impl<T> AsyncWrite for StubbornIo<T>
-where
-    T: UnderlyingIo + AsyncWrite,
-{
-    /// Writes to the underlying IO item.
+    /// Re-sends the history retained under [`WriteFailurePolicy::Replay`] after a
+    /// reconnect, one retained write at a time through `write`.
     ///
-    /// If a write reveals a disconnect (or one is already in progress), behavior
-    /// depends on [`WriteFailurePolicy`]:
+    /// Returns `Ready(Ok(()))` once nothing is left to replay. A disconnect
+    /// detected mid-replay schedules the next reconnect and returns `Pending`; the
+    /// replay then restarts from the beginning on the following connection.
+    fn poll_replay<F>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        write: &mut F,
+    ) -> Poll<io::Result<()>>
+    where
+        F: FnMut(&mut T, &mut Context<'_>, &[IoSlice<'_>]) -> Poll<io::Result<usize>>,
+    {
+        loop {
+            let this = &mut *self;
+            let Some(replay) = this.replay.as_mut() else {
+                return Poll::Ready(Ok(()));
+            };
+            let Some(chunk) = replay.pending() else {
+                return Poll::Ready(Ok(()));
+            };
+            match write(live_io(&mut this.underlying_io), cx, &[IoSlice::new(chunk)]) {
+                Poll::Ready(Ok(0)) => {
+                    return poll_err(ErrorKind::WriteZero, "failed to replay retained bytes");
+                }
+                Poll::Ready(Ok(n)) => {
+                    this.stats.add_written(n);
+                    if let Some(bytes) = replay.advance(n) {
+                        info!("{}Replayed {bytes} byte(s)", this.log_prefix);
+                        this.options.events.emit(ReconnectEvent::Replayed { bytes });
+                    }
+                }
+                Poll::Ready(Err(err)) if this.is_disconnect_error(&err) => {
+                    warn!("{}Replay disconnect detected: {err:?}", this.log_prefix);
+                    self.on_disconnect(cx, DisconnectReason::IoError(err.kind()));
+                    return Poll::Pending;
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Finishes a heartbeat that has been started, through `write`. Returns
+    /// `Ready(Ok(true))` if this call completed one, `Ready(Ok(false))` if none
+    /// was in flight. Disconnects are handled as in [`Self::poll_replay`].
+    fn poll_heartbeat_in_flight<F>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        write: &mut F,
+    ) -> Poll<io::Result<bool>>
+    where
+        F: FnMut(&mut T, &mut Context<'_>, &[IoSlice<'_>]) -> Poll<io::Result<usize>>,
+    {
+        loop {
+            let this = &mut *self;
+            let Some(heartbeat) = this.heartbeat.as_mut() else {
+                return Poll::Ready(Ok(false));
+            };
+            let Some(sent) = heartbeat.in_flight else {
+                return Poll::Ready(Ok(false));
+            };
+            let remaining = IoSlice::new(&heartbeat.payload[sent..]);
+            match write(live_io(&mut this.underlying_io), cx, &[remaining]) {
+                Poll::Ready(Ok(0)) => {
+                    return poll_err(ErrorKind::WriteZero, "failed to write heartbeat");
+                }
+                Poll::Ready(Ok(n)) => {
+                    this.stats.add_written(n);
+                    if sent + n == heartbeat.payload.len() {
+                        heartbeat.in_flight = None;
+                        heartbeat.reset();
+                        debug!("{}Heartbeat sent", this.log_prefix);
+                        return Poll::Ready(Ok(true));
+                    }
+                    heartbeat.in_flight = Some(sent + n);
+                }
+                Poll::Ready(Err(err)) if this.is_disconnect_error(&err) => {
+                    warn!("{}Heartbeat disconnect detected: {err:?}", this.log_prefix);
+                    self.on_disconnect(cx, DisconnectReason::IoError(err.kind()));
+                    return Poll::Pending;
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Shared write path for every write-like operation on the wrapped IO
+    /// (`AsyncWrite::poll_write`, `poll_write_vectored`, datagram `poll_send`, ...).
     ///
-    /// * `Backpressure` (default): return `Poll::Pending`, hold the buffer, wake
-    ///   when (re)connection completes.
-    /// * `DropAndNotify`: return `Poll::Ready(Ok(buf.len()))` to keep the caller's
-    ///   framing layer moving, while the bytes themselves are discarded. The
-    ///   reconnect machinery is engaged either way.
-    fn poll_write(
+    /// `bufs` are the bytes the caller asked to write; their total is what gets
+    /// reported (and dropped) under [`WriteFailurePolicy::DropAndNotify`], and
+    /// what gets retained under [`WriteFailurePolicy::Replay`]. `write` may be
+    /// called more than once: pending replay chunks and the remainder of a
+    /// started heartbeat go through it (as a single slice each) before `bufs` do.
+    pub(crate) fn poll_write_with<F>(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
-        buf: &[u8],
-    ) -> Poll<io::Result<usize>> {
+        bufs: &[IoSlice<'_>],
+        mut write: F,
+    ) -> Poll<io::Result<usize>>
+    where
+        F: FnMut(&mut T, &mut Context<'_>, &[IoSlice<'_>]) -> Poll<io::Result<usize>>,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
         let prefix = Arc::clone(&self.log_prefix);
         let policy = self.get_write_failure_policy();
+        let len: usize = bufs.iter().map(|b| b.len()).sum();
         match &mut self.status {
             Status::Connected => {
-                let poll = AsyncWrite::poll_write(Pin::new(&mut self.underlying_io), cx, buf);
+                ready!(self.as_mut().poll_replay(cx, &mut write))?;
+                ready!(self.as_mut().poll_heartbeat_in_flight(cx, &mut write))?;
+                let poll = write(live_io(&mut self.underlying_io), cx, bufs);
 
-                if self.is_write_disconnect_detected(&poll) {
+                if let Some(reason) = self.write_disconnect_reason(&poll) {
                     match policy {
-                        WriteFailurePolicy::Backpressure => {
+                        WriteFailurePolicy::Backpressure | WriteFailurePolicy::Replay { .. } => {
                             warn!("{prefix}Write disconnect detected. Applying back-pressure");
-                            self.on_disconnect(cx);
+                            self.on_disconnect(cx, reason);
                             Poll::Pending
                         }
                         WriteFailurePolicy::DropAndNotify => {
-                            error!(
-                                "{prefix}Write disconnect detected. Dropping {} byte(s)",
-                                buf.len()
-                            );
-                            (self.options.event_callback)(ReconnectEvent::WriteWhileDisconnected {
-                                bytes_dropped: buf.len(),
-                            });
-                            self.on_disconnect(cx);
-                            Poll::Ready(Ok(buf.len()))
+                            error!("{prefix}Write disconnect detected. Dropping {len} byte(s)");
+                            self.stats.add_dropped(len);
+                            self.options
+                                .events
+                                .emit(ReconnectEvent::WriteWhileDisconnected {
+                                    bytes_dropped: len,
+                                });
+                            self.on_disconnect(cx, reason);
+                            Poll::Ready(Ok(len))
                         }
                     }
                 } else {
+                    if let Poll::Ready(Ok(written)) = poll {
+                        self.stats.add_written(written);
+                        if let Some(replay) = &mut self.replay {
+                            replay.record(bufs, written, len);
+                        }
+                        if let Some(heartbeat) = &mut self.heartbeat {
+                            heartbeat.on_caller_write(written, len);
+                        }
+                    }
                     poll
                 }
             }
-            Status::Disconnected(_) => match policy {
-                WriteFailurePolicy::Backpressure => {
-                    warn!("{prefix}Write while disconnected. Applying back-pressure");
-                    self.poll_disconnect(cx);
-                    Poll::Pending
+            Status::Disconnected(_) => {
+                // A reconnect that completes now (or completed in the
+                // background) takes the write on the next poll, which
+                // `poll_disconnect` has already woken, instead of the policy.
+                // One that gives up fails the write under every policy: the
+                // bytes will never be sent.
+                self.as_mut().poll_disconnect(cx);
+                match self.status {
+                    Status::Connected => return Poll::Pending,
+                    Status::FailedAndExhausted => return exhausted_err(),
+                    Status::Disconnected(_) | Status::Closed => {}
                 }
-                WriteFailurePolicy::DropAndNotify => {
-                    error!(
-                        "{prefix}Write while disconnected. Dropping {} byte(s)",
-                        buf.len()
-                    );
-                    (self.options.event_callback)(ReconnectEvent::WriteWhileDisconnected {
-                        bytes_dropped: buf.len(),
-                    });
-                    self.poll_disconnect(cx);
-                    Poll::Ready(Ok(buf.len()))
+                match policy {
+                    WriteFailurePolicy::Backpressure | WriteFailurePolicy::Replay { .. } => {
+                        warn!("{prefix}Write while disconnected. Applying back-pressure");
+                        Poll::Pending
+                    }
+                    WriteFailurePolicy::DropAndNotify => {
+                        error!("{prefix}Write while disconnected. Dropping {len} byte(s)");
+                        self.stats.add_dropped(len);
+                        self.options
+                            .events
+                            .emit(ReconnectEvent::WriteWhileDisconnected { bytes_dropped: len });
+                        Poll::Ready(Ok(len))
+                    }
                 }
-            },
+            }
             Status::FailedAndExhausted => exhausted_err(),
             Status::Closed => closed_err(),
         }
     }
+}
+
+impl<T> StubbornIo<T>
+where
+    T: UnderlyingIo + AsyncWrite,
+{
+    /// Drives the heartbeat configured with
+    /// [`ReconnectOptions::with_heartbeat`], returning `Ready(Ok(()))` each time
+    /// one has been written and flushed.
+    ///
+    /// Heartbeats are not sent on their own: no timer or background task
+    /// writes them, and a stream whose `poll_heartbeat` is not being polled
+    /// sends none however long it idles. A writer that wants them polls this
+    /// next to its own writes (for split streams, through
+    /// [`StubbornWriteHalf::heartbeat`](super::StubbornWriteHalf::heartbeat)).
+    /// Without a configured heartbeat this never completes. Errors are those of the write path: `NotConnected` once the
+    /// stream is terminal, or a non-disconnect I/O error.
+    pub fn poll_heartbeat(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
+        loop {
+            match &self.status {
+                Status::Connected => {}
+                Status::Disconnected(_) => {
+                    self.poll_disconnect(cx);
+                    return Poll::Pending;
+                }
+                Status::FailedAndExhausted => return exhausted_err(),
+                Status::Closed => return closed_err(),
+            }
+            let Some(heartbeat) = self.heartbeat.as_mut() else {
+                return Poll::Pending;
+            };
+            if heartbeat.flush_pending {
+                ready!(AsyncWrite::poll_flush(self.as_mut(), cx))?;
+                if let Some(heartbeat) = &mut self.heartbeat {
+                    heartbeat.flush_pending = false;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if heartbeat.in_flight.is_none() {
+                ready!(heartbeat.poll_due(cx));
+                heartbeat.in_flight = Some(0);
+            }
+            ready!(self.as_mut().poll_replay(cx, &mut write_vectored::<T>))?;
+            if ready!(
+                self.as_mut()
+                    .poll_heartbeat_in_flight(cx, &mut write_vectored::<T>)
+            )? && let Some(heartbeat) = &mut self.heartbeat
+            {
+                heartbeat.flush_pending = true;
+            }
+        }
+    }
+
+    /// Waits for the next heartbeat to be sent. See [`Self::poll_heartbeat`].
+    ///
+    /// ```no_run
+    /// use sdre_stubborn_io::{ReconnectOptions, StubbornTcpStream};
+    /// use std::time::Duration;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # async fn example(mut rx: mpsc::Receiver<Vec<u8>>) -> std::io::Result<()> {
+    /// let opts = ReconnectOptions::new().with_heartbeat(Duration::from_secs(30), b"\n");
+    /// let mut tcp = StubbornTcpStream::connect_with_options("127.0.0.1:5550".parse().unwrap(), opts).await?;
+    /// loop {
+    ///     tokio::select! {
+    ///         msg = rx.recv() => match msg {
+    ///             Some(msg) => tcp.write_all(&msg).await?,
+    ///             None => break,
+    ///         },
+    ///         sent = tcp.heartbeat() => sent?,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn heartbeat(&mut self) -> io::Result<()> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_heartbeat(cx)).await
+    }
+}
+
+impl<T> AsyncRead for StubbornIo<T>
+where
+    T: UnderlyingIo + AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.poll_read_with(cx, buf, |io, cx, buf| {
+            AsyncRead::poll_read(Pin::new(io), cx, buf)
+        })
+    }
+}
+
+impl<T> AsyncWrite for StubbornIo<T>
+where
+    T: UnderlyingIo + AsyncWrite,
+{
+    /// Writes to the underlying IO item.
+    ///
+    /// If a write reveals a disconnect (or one is already in progress), behavior
+    /// depends on [`WriteFailurePolicy`]:
+    ///
+    /// * `Backpressure` (default): return `Poll::Pending`, hold the buffer, wake
+    ///   when (re)connection completes.
+    /// * `DropAndNotify`: return `Poll::Ready(Ok(buf.len()))` to keep the caller's
+    ///   framing layer moving, while the bytes themselves are discarded. The
+    ///   reconnect machinery is engaged either way.
+    /// * `Replay`: as `Backpressure`; after a reconnect the retained history is
+    ///   re-sent before `buf`.
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_with(cx, &[IoSlice::new(buf)], |io, cx, bufs| {
+            AsyncWrite::poll_write(Pin::new(io), cx, first_slice(bufs))
+        })
+    }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
         match &mut self.status {
             Status::Connected => {
-                let poll = AsyncWrite::poll_flush(Pin::new(&mut self.underlying_io), cx);
+                ready!(self.as_mut().poll_replay(cx, &mut write_vectored::<T>))?;
+                let poll = AsyncWrite::poll_flush(Pin::new(live_io(&mut self.underlying_io)), cx);
 
-                if self.is_write_disconnect_detected(&poll) {
-                    self.on_disconnect(cx);
+                self.write_disconnect_reason(&poll).map_or(poll, |reason| {
+                    self.on_disconnect(cx, reason);
                     Poll::Pending
-                } else {
-                    poll
-                }
+                })
             }
             Status::Disconnected(_) => {
                 self.poll_disconnect(cx);
@@ -548,9 +1107,12 @@ where
     }
 
     fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        #[cfg(feature = "tracing")]
+        let _span = self.enter_span();
         match &mut self.status {
             Status::Connected => {
-                let poll = AsyncWrite::poll_shutdown(Pin::new(&mut self.underlying_io), cx);
+                let poll =
+                    AsyncWrite::poll_shutdown(Pin::new(live_io(&mut self.underlying_io)), cx);
                 if poll.is_ready() {
                     // Whether the shutdown succeeded or errored, the caller has
                     // expressed intent to close. Transition to the terminal
@@ -558,6 +1120,8 @@ where
                     // ops surface a clean NotConnected (rather than triggering
                     // a reconnect via on_disconnect).
                     self.status = Status::Closed;
+                    set_state(&self.state, ConnectionState::Closed);
+                    self.stats.on_closed();
                 }
 
                 poll
@@ -566,6 +1130,8 @@ where
             // semantically already "closed enough" — transition and report it.
             Status::Disconnected(_) => {
                 self.status = Status::Closed;
+                set_state(&self.state, ConnectionState::Closed);
+                self.stats.on_closed();
                 closed_err()
             }
             Status::FailedAndExhausted => exhausted_err(),
@@ -580,65 +1146,16 @@ where
     /// caller's framing cursor advancing. Those bytes are not actually
     /// transmitted; this is the documented drop semantic of the policy.
     fn poll_write_vectored(
-        mut self: Pin<&mut Self>,
+        self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         bufs: &[IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        let prefix = Arc::clone(&self.log_prefix);
-        let policy = self.get_write_failure_policy();
-        let total: usize = bufs.iter().map(|b| b.len()).sum();
-        match &mut self.status {
-            Status::Connected => {
-                let poll =
-                    AsyncWrite::poll_write_vectored(Pin::new(&mut self.underlying_io), cx, bufs);
-
-                if self.is_write_disconnect_detected(&poll) {
-                    match policy {
-                        WriteFailurePolicy::Backpressure => {
-                            warn!("{prefix}Write disconnect detected. Applying back-pressure");
-                            self.on_disconnect(cx);
-                            Poll::Pending
-                        }
-                        WriteFailurePolicy::DropAndNotify => {
-                            error!(
-                                "{prefix}Write disconnect detected. Dropping {total} byte(s) across {} buffer(s)",
-                                bufs.len()
-                            );
-                            (self.options.event_callback)(ReconnectEvent::WriteWhileDisconnected {
-                                bytes_dropped: total,
-                            });
-                            self.on_disconnect(cx);
-                            Poll::Ready(Ok(total))
-                        }
-                    }
-                } else {
-                    poll
-                }
-            }
-            Status::Disconnected(_) => match policy {
-                WriteFailurePolicy::Backpressure => {
-                    warn!("{prefix}Write while disconnected. Applying back-pressure");
-                    self.poll_disconnect(cx);
-                    Poll::Pending
-                }
-                WriteFailurePolicy::DropAndNotify => {
-                    error!(
-                        "{prefix}Write while disconnected. Dropping {total} byte(s) across {} buffer(s)",
-                        bufs.len()
-                    );
-                    (self.options.event_callback)(ReconnectEvent::WriteWhileDisconnected {
-                        bytes_dropped: total,
-                    });
-                    self.poll_disconnect(cx);
-                    Poll::Ready(Ok(total))
-                }
-            },
-            Status::FailedAndExhausted => exhausted_err(),
-            Status::Closed => closed_err(),
-        }
+        self.poll_write_with(cx, bufs, write_vectored::<T>)
     }
 
     fn is_write_vectored(&self) -> bool {
-        self.underlying_io.is_write_vectored()
+        self.underlying_io
+            .as_ref()
+            .is_some_and(AsyncWrite::is_write_vectored)
     }
 }
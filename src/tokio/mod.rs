@@ -1,12 +1,39 @@
 //! Provides functionality related to asynchronous IO.
 //!
-//! Includes concrete ready to use structs such as [`StubbornTcpStream`] as well as
-//! the [`UnderlyingIo`] trait and [`StubbornIo`] struct
+//! Includes concrete ready to use structs such as [`StubbornTcpStream`],
+//! [`StubbornUdpSocket`], (on Unix) `StubbornUnixStream` and (with the `tls`
+//! feature) `StubbornTlsStream`, the frame-atomic `StubbornFramed` wrapper (with
+//! the `codec` feature), as well as the [`UnderlyingIo`] trait and [`StubbornIo`] struct
 //! needed to create custom stubborn io types yourself.
 
+mod events;
+#[cfg(feature = "codec")]
+mod framed;
+mod heartbeat;
 mod io;
+mod reconnect;
+mod replay;
+mod split;
+mod stats;
 mod tcp;
+#[cfg(feature = "tls")]
+mod tls;
+mod udp;
+#[cfg(unix)]
+mod unix;
 
-pub use self::io::{StubbornIo, UnderlyingIo};
+pub use self::events::ReconnectEventStream;
+#[cfg(feature = "codec")]
+pub use self::framed::StubbornFramed;
+pub use self::io::{ConnectionState, StubbornIo, UnderlyingIo};
+pub use self::split::{StubbornReadHalf, StubbornWriteHalf};
+pub use self::stats::{ConnectionStats, OUTAGE_BUCKETS, OutageHistogram, StatsSnapshot};
 
-pub use self::tcp::StubbornTcpStream;
+pub use self::tcp::{
+    HostPort, Resolver, StubbornTcpHostStream, StubbornTcpStream, SystemResolver, TcpHostStream,
+};
+#[cfg(feature = "tls")]
+pub use self::tls::{StubbornTlsStream, TlsContext, rustls};
+pub use self::udp::StubbornUdpSocket;
+#[cfg(unix)]
+pub use self::unix::StubbornUnixStream;
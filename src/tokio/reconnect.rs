@@ -0,0 +1,357 @@
+//! The connect sequence run while a `StubbornIo` is disconnected: each attempt,
+//! the waits between them from the retries iterator, and the events they emit.
+
+use super::io::{ConnectionState, UnderlyingIo};
+use super::stats::ConnectionStats;
+use crate::config::{
+    ConnectSequence, DurationIterator, Established, EstablishedHook, EventListeners,
+    ExhaustedReason, ReconnectEvent, RetryDecision, RetryPolicy, SpawnSequence,
+};
+use log::{error, info, warn};
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, sleep_until, timeout, timeout_at};
+
+/// Run `establish`, followed by the optional `on_established` hook, with the
+/// optional per-attempt timeout from `ReconnectOptions` covering both.
+/// Elapsed timeouts surface as `io::ErrorKind::TimedOut` so the reconnect machinery
+/// treats them as a failed attempt and proceeds to the next backoff step.
+async fn establish_with_timeout<T: UnderlyingIo>(
+    ctx: T::Context,
+    deadline: Option<Duration>,
+    on_established: Option<EstablishedHook<T>>,
+) -> io::Result<T> {
+    let attempt = async move {
+        let io = T::establish(ctx).await?;
+        match on_established {
+            Some(hook) => hook(io).await,
+            None => Ok(io),
+        }
+    };
+    if let Some(d) = deadline {
+        timeout(d, attempt).await.unwrap_or_else(|_| {
+            Err(io::Error::new(
+                ErrorKind::TimedOut,
+                "connect attempt exceeded configured connect_timeout",
+            ))
+        })
+    } else {
+        attempt.await
+    }
+}
+
+/// The connection context, shared between a `StubbornIo` and its running
+/// reconnect sequence so that a replaced context applies from the next attempt.
+pub(super) type SharedContext<C> = Arc<Mutex<C>>;
+
+/// How far ahead [`deadline_after`] places a deadline that `Instant` cannot
+/// represent; the same horizon `tokio::time::sleep` saturates to.
+const FAR_FUTURE: Duration = Duration::from_secs(86400 * 365 * 30);
+
+/// The instant `delay` from now, saturating to a far-future instant instead of
+/// overflowing (e.g. for `Duration::MAX`).
+pub(super) fn deadline_after(delay: Duration) -> Instant {
+    let now = Instant::now();
+    now.checked_add(delay).unwrap_or_else(|| now + FAR_FUTURE)
+}
+
+pub(super) fn lock<C>(ctx: &Mutex<C>) -> MutexGuard<'_, C> {
+    ctx.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Publishes `state` to [`StubbornIo::state_watcher`](super::StubbornIo::state_watcher)
+/// receivers, waking them only if it differs from the current one.
+pub(super) fn set_state(tx: &watch::Sender<ConnectionState>, state: ConnectionState) {
+    tx.send_if_modified(|current| {
+        let changed = *current != state;
+        *current = state;
+        changed
+    });
+}
+
+/// A scheduled connect attempt: its number, the delay it was scheduled with,
+/// and when it is due.
+type Step = (usize, Duration, Instant);
+
+/// Everything a connect sequence needs, detached from the `StubbornIo` so the
+/// sequence can run on a task of its own.
+pub(super) struct ReconnectPlan<T: UnderlyingIo> {
+    pub(super) ctx: SharedContext<T::Context>,
+    pub(super) connect_timeout: Option<Duration>,
+    pub(super) on_established: Option<EstablishedHook<T>>,
+    pub(super) events: Arc<EventListeners>,
+    pub(super) state: watch::Sender<ConnectionState>,
+    pub(super) stats: Arc<ConnectionStats>,
+    pub(super) retry_policy: Option<Arc<dyn RetryPolicy>>,
+    /// When the sequence gives up regardless of the retries left.
+    pub(super) deadline: Option<Instant>,
+    /// The stream's span, parent of one span per attempt.
+    #[cfg(feature = "tracing")]
+    pub(super) span: tracing::Span,
+    pub(super) log_prefix: Arc<str>,
+    /// The initial connect of a new stream: attempt 0 runs without delay, and
+    /// `exit_if_first_connect_fails` applies to it.
+    pub(super) initial: bool,
+    pub(super) exit_if_first_connect_fails: bool,
+}
+
+impl<T: UnderlyingIo> ReconnectPlan<T> {
+    /// Makes `attempt` the next one, due after `delay`, and reports it.
+    /// Attempt 0, the initial connect, is not announced as a reconnect.
+    fn schedule(&self, attempt: usize, delay: Duration) -> Step {
+        let at = deadline_after(delay);
+        if attempt > 0 {
+            info!(
+                "{}Will perform reconnect attempt #{attempt} in {delay:?}.",
+                self.log_prefix
+            );
+            self.events
+                .emit(ReconnectEvent::ReconnectScheduled { attempt, delay });
+        }
+        set_state(
+            &self.state,
+            ConnectionState::Reconnecting {
+                attempt,
+                next_at: at,
+            },
+        );
+        (attempt, delay, at)
+    }
+
+    /// The wait before `attempt`, made necessary by `error`: from the retry
+    /// policy, or from `retries` if there is none or it defers to them. An
+    /// error ends the sequence.
+    fn next_delay(
+        &self,
+        retries: &mut DurationIterator,
+        error: Option<&io::Error>,
+        attempt: usize,
+    ) -> Result<Duration, ExhaustedReason> {
+        let decision = match (&self.retry_policy, error) {
+            (Some(policy), Some(error)) => policy.decide(error, attempt),
+            _ => RetryDecision::UseDefault,
+        };
+        match decision {
+            RetryDecision::Retry(delay) => Ok(delay),
+            RetryDecision::GiveUp => {
+                error!(
+                    "{}Retry policy declined attempt #{attempt}. Giving up.",
+                    self.log_prefix
+                );
+                Err(ExhaustedReason::RetryPolicy)
+            }
+            RetryDecision::UseDefault => retries.next().ok_or(ExhaustedReason::RetriesExhausted),
+        }
+    }
+
+    /// Reports that the sequence gave up for `reason`.
+    fn exhaust(&self, reason: ExhaustedReason) {
+        self.events.emit(ReconnectEvent::Exhausted { reason });
+        set_state(&self.state, ConnectionState::Exhausted);
+    }
+
+    /// Connects, starting with `first` and then retrying after each delay from
+    /// `retries`, until an attempt succeeds or the stream gives up. On success,
+    /// the rest of `retries` is handed back along with the connection. On giving
+    /// up, `Exhausted` has been emitted and the error is that of the last
+    /// attempt, or `TimedOut` if the deadline passed.
+    async fn run(
+        self,
+        first: Result<Step, ExhaustedReason>,
+        mut retries: DurationIterator,
+    ) -> Connected<T> {
+        let prefix = Arc::clone(&self.log_prefix);
+        let mut next = first;
+        let mut last_err = None;
+        let reason = loop {
+            let step = match next {
+                Ok(step) => step,
+                Err(reason) => break reason,
+            };
+            let (attempt, _, at) = step;
+            let connect = async {
+                if attempt > 0 {
+                    sleep_until(at).await;
+                    info!("{prefix}Attempting reconnect #{attempt} now.");
+                }
+                self.attempt(attempt).await
+            };
+            #[cfg(feature = "tracing")]
+            let connect = tracing::Instrument::instrument(
+                connect,
+                tracing::info_span!(
+                    parent: &self.span,
+                    "connect_attempt",
+                    attempt,
+                    delay = ?step.1,
+                    error.kind = tracing::field::Empty,
+                ),
+            );
+            let result = match self.deadline {
+                Some(deadline) => match timeout_at(deadline, connect).await {
+                    Ok(result) => result,
+                    Err(_) => break ExhaustedReason::MaxDisconnectedDuration,
+                },
+                None => connect.await,
+            };
+            let err = match result {
+                Ok((io, since)) => return Ok((io, retries, since)),
+                Err(err) => err,
+            };
+            if let Some(reason) = self.gives_up(&err, attempt) {
+                self.exhaust(reason);
+                return Err(err);
+            }
+            next = self
+                .next_delay(&mut retries, Some(&err), attempt + 1)
+                .map(|delay| self.schedule(attempt + 1, delay));
+            last_err = Some(err);
+        };
+
+        match reason {
+            ExhaustedReason::RetriesExhausted => {
+                error!("{prefix}No more re-connect retries remaining. Giving up.");
+            }
+            ExhaustedReason::MaxDisconnectedDuration => {
+                error!("{prefix}Disconnected for longer than allowed. Giving up.");
+                last_err = Some(io::Error::new(
+                    ErrorKind::TimedOut,
+                    "disconnected for longer than max_disconnected_duration",
+                ));
+            }
+            _ => {}
+        }
+        self.exhaust(reason);
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(
+                ErrorKind::NotConnected,
+                "no reconnect attempts were allowed",
+            )
+        }))
+    }
+
+    /// Makes one connect attempt, returning the connection and the instant it
+    /// was established.
+    async fn attempt(&self, attempt: usize) -> io::Result<(T, Instant)> {
+        let prefix = &self.log_prefix;
+        let ctx = lock(&self.ctx).clone();
+        let established = establish_with_timeout(
+            ctx.clone(),
+            self.connect_timeout,
+            self.on_established.clone(),
+        );
+        match established.await {
+            Ok(io) => {
+                let since = Instant::now();
+                if self.initial && attempt == 0 {
+                    info!("{prefix}Initial connection succeeded.");
+                } else {
+                    info!("{prefix}Connection re-established");
+                }
+                // The transport may report the address it picked; announce it
+                // ahead of `Connected`.
+                self.stats.on_connected();
+                if let Some(addr) = io.resolved_addr(&ctx) {
+                    self.events
+                        .emit(ReconnectEvent::AddressResolved { attempt, addr });
+                }
+                self.events.emit(ReconnectEvent::Connected { attempt });
+                set_state(&self.state, ConnectionState::Connected);
+                Ok((io, since))
+            }
+            Err(err) => {
+                warn!("{prefix}Connection attempt #{attempt} failed: {err:?}");
+                #[cfg(feature = "tracing")]
+                tracing::Span::current().record("error.kind", tracing::field::debug(err.kind()));
+                self.stats.on_connect_failed(err.kind());
+                self.events.emit(ReconnectEvent::ConnectFailed {
+                    error: &err,
+                    attempt,
+                });
+                Err(err)
+            }
+        }
+    }
+
+    /// Why the failed `attempt` ends the sequence without consuming further
+    /// retries, if it does.
+    fn gives_up(&self, err: &io::Error, attempt: usize) -> Option<ExhaustedReason> {
+        let prefix = &self.log_prefix;
+        if T::is_fatal_connect_error(err) {
+            error!("{prefix}Connection attempt #{attempt} failed with a fatal error. Giving up.");
+            Some(ExhaustedReason::FatalError)
+        } else if self.initial && attempt == 0 && self.exit_if_first_connect_fails {
+            error!("{prefix}Bailing after initial connection failure.");
+            Some(ExhaustedReason::FirstConnectFailed)
+        } else {
+            None
+        }
+    }
+}
+
+/// Outcome of a connect sequence.
+pub(super) type Connected<T> = io::Result<Established<T>>;
+
+/// A running connect sequence, resolving to the new connection.
+pub(super) enum Reconnect<T> {
+    /// Makes progress only while the stream is polled.
+    Inline(ConnectSequence<T>),
+    /// Runs on a spawned task, which is aborted when this is dropped.
+    Background(JoinHandle<Connected<T>>),
+}
+
+impl<T: UnderlyingIo> Reconnect<T> {
+    /// Starts the sequence; `lost` is the error that ended the previous
+    /// connection, if any.
+    pub(super) fn start(
+        plan: ReconnectPlan<T>,
+        mut retries: DurationIterator,
+        lost: Option<&io::Error>,
+        spawner: Option<SpawnSequence<T>>,
+    ) -> Self {
+        // The first attempt is scheduled before returning, so it has been
+        // reported by the time the disconnect that caused it is.
+        let first = if plan.initial {
+            Ok(plan.schedule(0, Duration::ZERO))
+        } else {
+            plan.next_delay(&mut retries, lost, 1)
+                .map(|delay| plan.schedule(1, delay))
+        };
+        #[cfg(feature = "tracing")]
+        let span = plan.span.clone();
+        let sequence = plan.run(first, retries);
+        #[cfg(feature = "tracing")]
+        let sequence = tracing::Instrument::instrument(sequence, span);
+        match spawner {
+            Some(spawner) => Self::Background(spawner(Box::pin(sequence))),
+            None => Self::Inline(Box::pin(sequence)),
+        }
+    }
+}
+
+impl<T> Future for Reconnect<T> {
+    type Output = Connected<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut() {
+            Self::Inline(sequence) => sequence.as_mut().poll(cx),
+            Self::Background(task) => Pin::new(task)
+                .poll(cx)
+                .map(|joined| joined.unwrap_or_else(|err| Err(io::Error::other(err)))),
+        }
+    }
+}
+
+impl<T> Drop for Reconnect<T> {
+    fn drop(&mut self) {
+        if let Self::Background(task) = self {
+            task.abort();
+        }
+    }
+}
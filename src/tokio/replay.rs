@@ -0,0 +1,213 @@
+//! Bounded retransmit buffer backing [`WriteFailurePolicy::Replay`](crate::config::WriteFailurePolicy::Replay).
+
+use std::collections::VecDeque;
+use std::io::IoSlice;
+
+/// Remembers the most recent successful writes, one chunk per caller message,
+/// and replays them on a fresh connection.
+///
+/// A write the transport only partly accepted is continued by the caller's next
+/// write (as `write_all` does), so both go into the same chunk. Eviction is
+/// chunk-granular: the oldest whole chunks are dropped until the retained total
+/// fits in `capacity`, and a message larger than `capacity` is not retained at
+/// all. A replay therefore never starts in the middle of a message (for
+/// datagram transports: never in the middle of a datagram).
+pub(super) struct ReplayBuffer {
+    capacity: usize,
+    chunks: VecDeque<Vec<u8>>,
+    len: usize,
+    /// Replay position as (chunk index, offset within chunk); `None` when no
+    /// replay is pending.
+    cursor: Option<(usize, usize)>,
+    /// The last write was short, so the next one continues the same message.
+    mid_message: bool,
+    /// The message being continued outgrew `capacity` and is not retained.
+    skipping: bool,
+}
+
+impl ReplayBuffer {
+    pub(super) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            chunks: VecDeque::new(),
+            len: 0,
+            cursor: None,
+            mid_message: false,
+            skipping: false,
+        }
+    }
+
+    /// Records the first `written` of the `requested` bytes in `bufs`, as a new
+    /// chunk or, if the previous write was short, as the rest of the last one.
+    pub(super) fn record(&mut self, bufs: &[IoSlice<'_>], written: usize, requested: usize) {
+        debug_assert!(self.cursor.is_none(), "recording during a pending replay");
+        if written == 0 {
+            return;
+        }
+        let continues = self.mid_message;
+        self.mid_message = written < requested;
+        if !continues {
+            self.skipping = false;
+            self.chunks.push_back(Vec::new());
+        }
+        if self.skipping {
+            return;
+        }
+        let Some(chunk) = self.chunks.back_mut() else {
+            return;
+        };
+        let mut remaining = written;
+        for buf in bufs {
+            let take = buf.len().min(remaining);
+            chunk.extend_from_slice(&buf[..take]);
+            remaining -= take;
+            if remaining == 0 {
+                break;
+            }
+        }
+        self.len += written;
+        if chunk.len() > self.capacity {
+            self.chunks.clear();
+            self.len = 0;
+            self.skipping = true;
+            return;
+        }
+        while self.len > self.capacity {
+            if let Some(evicted) = self.chunks.pop_front() {
+                self.len -= evicted.len();
+            }
+        }
+    }
+
+    /// Forgets the message a short write left unfinished, for a caller that
+    /// will write it again from its first byte instead of continuing it.
+    pub(super) fn discard_partial(&mut self) {
+        if !std::mem::take(&mut self.mid_message) {
+            return;
+        }
+        if !std::mem::take(&mut self.skipping) {
+            if let Some(chunk) = self.chunks.pop_back() {
+                self.len -= chunk.len();
+            }
+        }
+        if self
+            .cursor
+            .is_some_and(|(chunk, _)| chunk >= self.chunks.len())
+        {
+            self.cursor = None;
+        }
+    }
+
+    /// Schedules everything currently retained for replay from the start.
+    pub(super) fn arm(&mut self) {
+        self.cursor = (!self.chunks.is_empty()).then_some((0, 0));
+    }
+
+    /// The unsent remainder of the chunk being replayed, if a replay is pending.
+    pub(super) fn pending(&self) -> Option<&[u8]> {
+        let (chunk, offset) = self.cursor?;
+        self.chunks.get(chunk).map(|c| &c[offset..])
+    }
+
+    /// Marks `n` bytes of [`Self::pending`] as sent. Returns the total number of
+    /// bytes replayed once the final chunk completes.
+    pub(super) fn advance(&mut self, n: usize) -> Option<usize> {
+        let (chunk, offset) = self.cursor?;
+        let offset = offset + n;
+        if offset < self.chunks[chunk].len() {
+            self.cursor = Some((chunk, offset));
+            None
+        } else if chunk + 1 < self.chunks.len() {
+            self.cursor = Some((chunk + 1, 0));
+            None
+        } else {
+            self.cursor = None;
+            Some(self.len)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReplayBuffer;
+    use std::io::IoSlice;
+
+    fn drain(buf: &mut ReplayBuffer) -> (Vec<u8>, Option<usize>) {
+        let mut out = Vec::new();
+        buf.arm();
+        let mut done = None;
+        while let Some(chunk) = buf.pending() {
+            out.extend_from_slice(chunk);
+            let n = chunk.len();
+            done = buf.advance(n);
+        }
+        (out, done)
+    }
+
+    #[test]
+    fn evicts_whole_chunks_oldest_first() {
+        let mut buf = ReplayBuffer::new(8);
+        buf.record(&[IoSlice::new(b"abc")], 3, 3);
+        buf.record(&[IoSlice::new(b"defg")], 4, 4);
+        buf.record(&[IoSlice::new(b"hi")], 2, 2);
+        assert_eq!(drain(&mut buf), (b"defghi".to_vec(), Some(6)));
+    }
+
+    #[test]
+    fn records_only_written_prefix_across_slices() {
+        let mut buf = ReplayBuffer::new(16);
+        buf.record(&[IoSlice::new(b"ab"), IoSlice::new(b"cdef")], 4, 4);
+        assert_eq!(drain(&mut buf), (b"abcd".to_vec(), Some(4)));
+    }
+
+    #[test]
+    fn oversized_chunk_is_not_retained() {
+        let mut buf = ReplayBuffer::new(4);
+        buf.record(&[IoSlice::new(b"ab")], 2, 2);
+        buf.record(&[IoSlice::new(b"toolong")], 7, 7);
+        buf.arm();
+        assert!(buf.pending().is_none());
+    }
+
+    #[test]
+    fn short_write_continuation_joins_the_same_chunk() {
+        let mut buf = ReplayBuffer::new(8);
+        buf.record(&[IoSlice::new(b"xy")], 2, 2);
+        buf.record(&[IoSlice::new(b"abcdef")], 4, 6);
+        buf.record(&[IoSlice::new(b"ef")], 2, 2);
+        buf.record(&[IoSlice::new(b"gh")], 2, 2);
+        // "xy" is evicted; "abcdef" survives whole rather than as "ef".
+        assert_eq!(drain(&mut buf), (b"abcdefgh".to_vec(), Some(8)));
+
+        let mut buf = ReplayBuffer::new(8);
+        buf.record(&[IoSlice::new(b"abcdefghij")], 4, 10);
+        buf.record(&[IoSlice::new(b"efghij")], 6, 6);
+        buf.record(&[IoSlice::new(b"k")], 1, 1);
+        // The 10-byte message outgrew the buffer and is dropped as a whole.
+        assert_eq!(drain(&mut buf), (b"k".to_vec(), Some(1)));
+    }
+
+    #[test]
+    fn discarded_partial_message_is_neither_replayed_nor_continued() {
+        let mut buf = ReplayBuffer::new(16);
+        buf.record(&[IoSlice::new(b"ab")], 2, 2);
+        buf.record(&[IoSlice::new(b"cdef")], 2, 4);
+        buf.arm();
+        buf.discard_partial();
+        assert_eq!(buf.pending(), Some(&b"ab"[..]));
+        assert_eq!(buf.advance(2), Some(2));
+        buf.record(&[IoSlice::new(b"cdef")], 4, 4);
+        assert_eq!(drain(&mut buf), (b"abcdef".to_vec(), Some(6)));
+    }
+
+    #[test]
+    fn partial_advance_resumes_mid_chunk() {
+        let mut buf = ReplayBuffer::new(16);
+        buf.record(&[IoSlice::new(b"abcd")], 4, 4);
+        buf.arm();
+        assert_eq!(buf.advance(1), None);
+        assert_eq!(buf.pending(), Some(&b"bcd"[..]));
+        assert_eq!(buf.advance(3), Some(4));
+        assert!(buf.pending().is_none());
+    }
+}
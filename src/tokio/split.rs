@@ -0,0 +1,212 @@
+//! Read and write halves of a [`StubbornIo`] that share its reconnect state machine.
+
+use super::io::{StubbornIo, UnderlyingIo};
+use super::reconnect::lock;
+use std::future::poll_fn;
+use std::io::{self, IoSlice};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+const READ: usize = 0;
+const WRITE: usize = 1;
+
+struct Shared<T: UnderlyingIo> {
+    io: StubbornIo<T>,
+    /// Waker of the most recent pending poll of each half, indexed by
+    /// [`READ`] / [`WRITE`].
+    wakers: [Option<Waker>; 2],
+}
+
+/// What the other half needs to hear about: a new connection, a lost one, or
+/// the end of the stream.
+const fn connection_state<T: UnderlyingIo>(io: &StubbornIo<T>) -> (u64, bool, bool) {
+    (io.generation(), io.is_connected(), io.is_terminated())
+}
+
+/// Polls the shared stream on behalf of `half`. If the poll changed the
+/// connection state, the other half is woken: it may be parked on a
+/// connection that no longer exists, or waiting for a reconnect that this
+/// poll completed.
+fn poll_half<T, R>(
+    shared: &Mutex<Shared<T>>,
+    half: usize,
+    cx: &mut Context<'_>,
+    poll: impl FnOnce(Pin<&mut StubbornIo<T>>, &mut Context<'_>) -> Poll<R>,
+) -> Poll<R>
+where
+    T: UnderlyingIo,
+{
+    let mut guard = lock(shared);
+    let before = connection_state(&guard.io);
+    let result = poll(Pin::new(&mut guard.io), cx);
+    if result.is_pending() {
+        guard.wakers[half] = Some(cx.waker().clone());
+    }
+    let other = if connection_state(&guard.io) == before {
+        None
+    } else {
+        guard.wakers[1 - half].take()
+    };
+    drop(guard);
+    if let Some(other) = other {
+        other.wake();
+    }
+    result
+}
+
+/// The reading half of a [`StubbornIo`], created by [`StubbornIo::into_split`].
+pub struct StubbornReadHalf<T: UnderlyingIo> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// The writing half of a [`StubbornIo`], created by [`StubbornIo::into_split`].
+pub struct StubbornWriteHalf<T: UnderlyingIo> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T: UnderlyingIo> StubbornIo<T> {
+    /// Splits the stream into a read half and a write half that can be used
+    /// from different tasks.
+    ///
+    /// Both halves drive the same reconnect state machine: a disconnect seen
+    /// by either one replaces the underlying connection once, and both halves
+    /// continue on the new connection. Whenever one half's poll connects,
+    /// loses or gives up on the connection, the other half is woken so it
+    /// never stays parked on a connection that has been replaced.
+    ///
+    /// The halves take turns on a lock that is held for a single poll only,
+    /// never across an `.await`.
+    #[must_use]
+    pub fn into_split(self) -> (StubbornReadHalf<T>, StubbornWriteHalf<T>) {
+        let shared = Arc::new(Mutex::new(Shared {
+            io: self,
+            wakers: [None, None],
+        }));
+        (
+            StubbornReadHalf {
+                shared: Arc::clone(&shared),
+            },
+            StubbornWriteHalf { shared },
+        )
+    }
+}
+
+impl<T: UnderlyingIo> StubbornReadHalf<T> {
+    /// Returns `true` if the shared stream is currently connected.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        lock(&self.shared).io.is_connected()
+    }
+
+    /// Returns `true` if the shared stream is in a terminal state.
+    #[must_use]
+    pub fn is_terminated(&self) -> bool {
+        lock(&self.shared).io.is_terminated()
+    }
+}
+
+impl<T: UnderlyingIo> StubbornWriteHalf<T> {
+    /// Returns `true` if the shared stream is currently connected.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        lock(&self.shared).io.is_connected()
+    }
+
+    /// Returns `true` if the shared stream is in a terminal state.
+    #[must_use]
+    pub fn is_terminated(&self) -> bool {
+        lock(&self.shared).io.is_terminated()
+    }
+}
+
+impl<T> StubbornWriteHalf<T>
+where
+    T: UnderlyingIo + AsyncWrite,
+{
+    /// Drives the heartbeat of the shared stream; see
+    /// [`StubbornIo::poll_heartbeat`].
+    pub fn poll_heartbeat(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        poll_half(&self.shared, WRITE, cx, StubbornIo::poll_heartbeat)
+    }
+
+    /// Waits for the next heartbeat to be sent; see [`StubbornIo::heartbeat`].
+    /// Drive it from the task that writes through this half.
+    ///
+    /// ```no_run
+    /// use sdre_stubborn_io::{ReconnectOptions, StubbornTcpStream};
+    /// use std::time::Duration;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # async fn example(mut rx: mpsc::Receiver<Vec<u8>>) -> std::io::Result<()> {
+    /// let opts = ReconnectOptions::new().with_heartbeat(Duration::from_secs(30), b"\n");
+    /// let tcp = StubbornTcpStream::connect_with_options("127.0.0.1:5550".parse().unwrap(), opts).await?;
+    /// let (_rd, mut wr) = tcp.into_split();
+    /// loop {
+    ///     tokio::select! {
+    ///         msg = rx.recv() => match msg {
+    ///             Some(msg) => wr.write_all(&msg).await?,
+    ///             None => break,
+    ///         },
+    ///         sent = wr.heartbeat() => sent?,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn heartbeat(&mut self) -> io::Result<()> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_heartbeat(cx)).await
+    }
+}
+
+impl<T> AsyncRead for StubbornReadHalf<T>
+where
+    T: UnderlyingIo + AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        poll_half(&self.shared, READ, cx, |io, cx| io.poll_read(cx, buf))
+    }
+}
+
+impl<T> AsyncWrite for StubbornWriteHalf<T>
+where
+    T: UnderlyingIo + AsyncWrite,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        poll_half(&self.shared, WRITE, cx, |io, cx| io.poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        poll_half(&self.shared, WRITE, cx, |io, cx| {
+            io.poll_write_vectored(cx, bufs)
+        })
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        lock(&self.shared).io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        poll_half(&self.shared, WRITE, cx, AsyncWrite::poll_flush)
+    }
+
+    /// Shuts down the shared stream; the read half then reports
+    /// `NotConnected` as well.
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        poll_half(&self.shared, WRITE, cx, AsyncWrite::poll_shutdown)
+    }
+}
@@ -0,0 +1,234 @@
+//! Counters backing [`StubbornIo::stats`](super::StubbornIo::stats).
+
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+
+/// Upper bounds of the [`OutageHistogram`] buckets.
+pub const OUTAGE_BUCKETS: [Duration; 9] = [
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+    Duration::from_secs(60),
+    Duration::from_secs(300),
+    Duration::from_secs(1800),
+];
+
+/// Live statistics of one [`StubbornIo`](super::StubbornIo), shared with its
+/// reconnect sequences and with anyone holding the handle from
+/// [`StubbornIo::stats_handle`](super::StubbornIo::stats_handle).
+///
+/// Updated as the stream runs; read it with [`Self::snapshot`].
+pub struct ConnectionStats {
+    reconnects: AtomicU64,
+    disconnects: AtomicU64,
+    failed_attempts: AtomicU64,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+    bytes_dropped: AtomicU64,
+    timing: Mutex<Timing>,
+}
+
+/// The parts that change together, at most a few times per connection.
+struct Timing {
+    /// Set while connected.
+    connected_since: Option<Instant>,
+    /// Set while not connected, until the stream is shut down.
+    disconnected_since: Option<Instant>,
+    /// Whether `disconnected_since` marks the loss of a connection (as opposed
+    /// to the wait for the initial one).
+    outage: bool,
+    /// Downtime of outages that have ended.
+    downtime: Duration,
+    last_connected: Option<SystemTime>,
+    last_error: Option<ErrorKind>,
+    /// Failed connect attempts per error kind, in order of first occurrence.
+    failures: Vec<(ErrorKind, u64)>,
+    outages: [u64; OUTAGE_BUCKETS.len() + 1],
+    outage_sum: Duration,
+}
+
+impl ConnectionStats {
+    pub(super) fn new() -> Self {
+        Self {
+            reconnects: AtomicU64::new(0),
+            disconnects: AtomicU64::new(0),
+            failed_attempts: AtomicU64::new(0),
+            bytes_read: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+            bytes_dropped: AtomicU64::new(0),
+            timing: Mutex::new(Timing {
+                connected_since: None,
+                disconnected_since: Some(Instant::now()),
+                outage: false,
+                downtime: Duration::ZERO,
+                last_connected: None,
+                last_error: None,
+                failures: Vec::new(),
+                outages: [0; OUTAGE_BUCKETS.len() + 1],
+                outage_sum: Duration::ZERO,
+            }),
+        }
+    }
+
+    fn timing(&self) -> MutexGuard<'_, Timing> {
+        self.timing.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn on_connected(&self) {
+        let now = Instant::now();
+        let mut timing = self.timing();
+        if let Some(since) = timing.disconnected_since.take() {
+            let down = now - since;
+            timing.downtime += down;
+            if timing.outage {
+                let bucket = OUTAGE_BUCKETS.partition_point(|bound| *bound < down);
+                timing.outages[bucket] += 1;
+                timing.outage_sum += down;
+            }
+        }
+        if timing.last_connected.is_some() {
+            self.reconnects.fetch_add(1, Ordering::Relaxed);
+        }
+        timing.connected_since = Some(now);
+        timing.last_connected = Some(SystemTime::now());
+    }
+
+    pub(super) fn on_connect_failed(&self, kind: ErrorKind) {
+        self.failed_attempts.fetch_add(1, Ordering::Relaxed);
+        let mut timing = self.timing();
+        timing.last_error = Some(kind);
+        match timing.failures.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => timing.failures.push((kind, 1)),
+        }
+    }
+
+    pub(super) fn on_disconnected(&self, kind: Option<ErrorKind>) {
+        self.disconnects.fetch_add(1, Ordering::Relaxed);
+        let mut timing = self.timing();
+        timing.connected_since = None;
+        timing.disconnected_since = Some(Instant::now());
+        timing.outage = true;
+        if kind.is_some() {
+            timing.last_error = kind;
+        }
+    }
+
+    /// The stream was shut down: neither up- nor downtime accrues any more.
+    pub(super) fn on_closed(&self) {
+        let mut timing = self.timing();
+        timing.connected_since = None;
+        if let Some(since) = timing.disconnected_since.take() {
+            timing.downtime += since.elapsed();
+        }
+    }
+
+    pub(super) fn add_read(&self, bytes: usize) {
+        self.bytes_read.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_written(&self, bytes: usize) {
+        self.bytes_written
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_dropped(&self, bytes: usize) {
+        self.bytes_dropped
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the current values.
+    #[must_use]
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let now = Instant::now();
+        let timing = self.timing();
+        let current_downtime = timing
+            .disconnected_since
+            .map_or(Duration::ZERO, |since| now - since);
+        let mut cumulative = 0;
+        let buckets = OUTAGE_BUCKETS
+            .iter()
+            .zip(timing.outages)
+            .map(|(bound, count)| {
+                cumulative += count;
+                (*bound, cumulative)
+            })
+            .collect();
+        let outages = OutageHistogram {
+            buckets,
+            count: timing.outages.iter().sum(),
+            sum: timing.outage_sum,
+        };
+        StatsSnapshot {
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            disconnects: self.disconnects.load(Ordering::Relaxed),
+            failed_attempts: self.failed_attempts.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            bytes_dropped: self.bytes_dropped.load(Ordering::Relaxed),
+            uptime: timing.connected_since.map(|since| now - since),
+            total_downtime: timing.downtime + current_downtime,
+            last_connected: timing.last_connected,
+            last_error: timing.last_error,
+            failed_attempts_by_kind: timing.failures.clone(),
+            outages,
+        }
+    }
+}
+
+/// Point-in-time copy of a [`ConnectionStats`].
+///
+/// Non-exhaustive so new statistics can be added without breaking existing code.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    /// Connections established after the first one.
+    pub reconnects: u64,
+    /// Established connections that were lost (or dropped on purpose, e.g. by
+    /// [`StubbornIo::force_reconnect`](super::StubbornIo::force_reconnect)).
+    pub disconnects: u64,
+    /// Connect attempts that failed, including the initial connect.
+    pub failed_attempts: u64,
+    /// Bytes read from the underlying connections.
+    pub bytes_read: u64,
+    /// Bytes written to the underlying connections, including replayed bytes
+    /// and heartbeats.
+    pub bytes_written: u64,
+    /// Bytes discarded under
+    /// [`WriteFailurePolicy::DropAndNotify`](crate::config::WriteFailurePolicy::DropAndNotify).
+    pub bytes_dropped: u64,
+    /// How long the current connection has been up; `None` while not connected.
+    pub uptime: Option<Duration>,
+    /// Time spent not connected since the stream was created, up to now or to
+    /// its shutdown. Includes the wait for the initial connection.
+    pub total_downtime: Duration,
+    /// Wall-clock time the most recent connection was established.
+    pub last_connected: Option<SystemTime>,
+    /// Kind of the most recent connect failure or disconnect error.
+    pub last_error: Option<ErrorKind>,
+    /// [`Self::failed_attempts`] broken down by error kind, in order of first
+    /// occurrence.
+    pub failed_attempts_by_kind: Vec<(ErrorKind, u64)>,
+    /// Durations of the outages that have ended in a reconnect.
+    pub outages: OutageHistogram,
+}
+
+/// Histogram of outage durations, from the loss of a connection to the next
+/// established one.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutageHistogram {
+    /// `(upper bound, outages no longer than it)` for each bound in
+    /// [`OUTAGE_BUCKETS`]. Counts are cumulative, as in Prometheus histograms.
+    pub buckets: Vec<(Duration, u64)>,
+    /// Total number of outages, including those longer than the last bound.
+    pub count: u64,
+    /// Sum of all outage durations.
+    pub sum: Duration,
+}
@@ -1,9 +1,15 @@
 use super::io::{StubbornIo, UnderlyingIo};
+use log::debug;
+use std::fmt;
 use std::future::Future;
-use std::io::{self, ErrorKind};
+use std::io::{self, ErrorKind, IoSlice};
 use std::net::SocketAddr;
+use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
-use tokio::net::TcpStream;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpStream, lookup_host};
 
 impl UnderlyingIo for TcpStream {
     type Context = SocketAddr;
@@ -12,6 +18,10 @@ impl UnderlyingIo for TcpStream {
         Box::pin(Self::connect(addr))
     }
 
+    fn describe_context(ctx: &Self::Context) -> Option<String> {
+        Some(format!("{ctx:?}"))
+    }
+
     /// TCP-specific disconnect classification. Drops `UnexpectedEof` (which a raw
     /// `TcpStream` poll cannot directly surface; an EOF on TCP manifests as a 0-byte
     /// read handled separately by [`UnderlyingIo::is_final_read`]).
@@ -36,10 +46,10 @@ impl UnderlyingIo for TcpStream {
 /// A drop in replacement for tokio's [`TcpStream`](tokio::net::TcpStream), with the
 /// distinction that it will automatically attempt to reconnect in the face of connectivity failures.
 ///
-/// Resolution of host strings (DNS) is intentionally **not** performed by this crate;
-/// callers must hand in an already-resolved [`SocketAddr`]. Wrapping a name+port into a
-/// custom [`UnderlyingIo`] implementation gives the caller control over caching and
-/// re-resolution policy.
+/// This type takes an already-resolved [`SocketAddr`] and never performs DNS. To
+/// re-resolve a host name on every reconnect use [`StubbornTcpHostStream`]; for
+/// other caching or re-resolution policies wrap a name+port into a custom
+/// [`UnderlyingIo`] implementation.
 ///
 /// ```
 /// use sdre_stubborn_io::StubbornTcpStream;
@@ -54,3 +64,251 @@ impl UnderlyingIo for TcpStream {
 /// };
 /// ```
 pub type StubbornTcpStream = StubbornIo<TcpStream>;
+
+/// Resolves a host name into candidate socket addresses for
+/// [`StubbornTcpHostStream`]. Invoked on **every** (re)connect attempt.
+///
+/// The default [`SystemResolver`] defers to the operating system via
+/// [`tokio::net::lookup_host`]; tests can inject a fake implementation so no
+/// network or DNS server is required.
+pub trait Resolver: Send + Sync {
+    /// Resolve `host` into the addresses to try, in order, for `port`.
+    fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send>>;
+}
+
+/// [`Resolver`] backed by the system resolver ([`tokio::net::lookup_host`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl Resolver for SystemResolver {
+    fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send>> {
+        let host = host.to_owned();
+        Box::pin(async move { Ok(lookup_host((host, port)).await?.collect()) })
+    }
+}
+
+/// Connection context for [`StubbornTcpHostStream`]: a host name and port that
+/// are re-resolved through a [`Resolver`] on every (re)connect.
+#[derive(Clone)]
+pub struct HostPort {
+    host: Arc<str>,
+    port: u16,
+    resolver: Arc<dyn Resolver>,
+}
+
+impl HostPort {
+    /// Target `host:port`, resolved through the [`SystemResolver`].
+    #[must_use]
+    pub fn new(host: impl Into<Arc<str>>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            resolver: Arc::new(SystemResolver),
+        }
+    }
+
+    /// Replaces the resolver used for every (re)connect attempt.
+    #[must_use]
+    pub fn with_resolver(mut self, resolver: impl Resolver + 'static) -> Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+
+    /// The host name that will be resolved.
+    #[must_use]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The port every resolved address is paired with.
+    #[must_use]
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Resolves and connects, trying every returned address in order. Returns the
+    /// stream together with the address that accepted the connection.
+    pub(crate) async fn connect(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        let addrs = self.resolver.resolve(&self.host, self.port).await?;
+        let mut last_err = None;
+        for addr in &addrs {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => {
+                    debug!("{self}: connected to resolved address {addr}");
+                    return Ok((stream, *addr));
+                }
+                Err(e) => {
+                    debug!("{self}: connect to resolved address {addr} failed: {e}");
+                    last_err = Some((*addr, e));
+                }
+            }
+        }
+        Err(match last_err {
+            Some((addr, e)) => io::Error::new(
+                e.kind(),
+                format!(
+                    "{self}: all {} resolved address(es) failed; last ({addr}): {e}",
+                    addrs.len()
+                ),
+            ),
+            None => io::Error::new(
+                ErrorKind::NotFound,
+                format!("{self}: resolver returned no addresses"),
+            ),
+        })
+    }
+}
+
+impl fmt::Debug for HostPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostPort")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for HostPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// A [`TcpStream`](tokio::net::TcpStream) that was reached by resolving a [`HostPort`].
+///
+/// Derefs to the inner `TcpStream`; [`Self::resolved_addr`] reports which of the
+/// resolved addresses accepted the connection.
+#[derive(Debug)]
+pub struct TcpHostStream {
+    stream: TcpStream,
+    addr: SocketAddr,
+}
+
+impl TcpHostStream {
+    /// The resolved address this stream is connected to.
+    #[must_use]
+    pub const fn resolved_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Consumes the wrapper, returning the inner `TcpStream`.
+    #[must_use]
+    pub fn into_inner(self) -> TcpStream {
+        self.stream
+    }
+}
+
+impl Deref for TcpHostStream {
+    type Target = TcpStream;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl DerefMut for TcpHostStream {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stream
+    }
+}
+
+impl UnderlyingIo for TcpHostStream {
+    type Context = HostPort;
+
+    /// Resolves the host through the context's [`Resolver`] and tries every
+    /// returned address in order, so DNS changes are picked up on reconnect.
+    fn establish(ctx: HostPort) -> Pin<Box<dyn Future<Output = io::Result<Self>> + Send>> {
+        Box::pin(async move {
+            let (stream, addr) = ctx.connect().await?;
+            Ok(Self { stream, addr })
+        })
+    }
+
+    fn describe_context(ctx: &Self::Context) -> Option<String> {
+        Some(format!("{ctx:?}"))
+    }
+
+    /// Same classification as [`TcpStream`](tokio::net::TcpStream).
+    fn is_disconnect_error(&self, err: &io::Error) -> bool {
+        self.stream.is_disconnect_error(err)
+    }
+
+    fn resolved_addr(&self, _ctx: &HostPort) -> Option<SocketAddr> {
+        Some(self.addr)
+    }
+}
+
+impl AsyncRead for TcpHostStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TcpHostStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
+
+/// A reconnecting TCP stream addressed by host name rather than by [`SocketAddr`].
+///
+/// The [`HostPort`] context is re-resolved on **every** (re)connect, and each
+/// resolved address is tried in order until one accepts.
+///
+/// The address that was used is reported through
+/// [`ReconnectEvent::AddressResolved`](crate::config::ReconnectEvent::AddressResolved)
+/// on every successful attempt, so a feeder whose DNS target moves is visible to
+/// event observers.
+///
+/// ```
+/// use sdre_stubborn_io::StubbornTcpHostStream;
+/// use sdre_stubborn_io::tokio::HostPort;
+/// use tokio::io::AsyncWriteExt;
+///
+/// async {
+///     let target = HostPort::new("feed.example.com", 5550);
+///     let mut tcp_stream = StubbornTcpHostStream::connect(target).await.unwrap();
+///     tcp_stream.write_all(b"hello world!").await.unwrap();
+/// };
+/// ```
+pub type StubbornTcpHostStream = StubbornIo<TcpHostStream>;
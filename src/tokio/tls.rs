@@ -0,0 +1,170 @@
+use super::io::{StubbornIo, UnderlyingIo};
+use super::tcp::HostPort;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{AlertDescription, ClientConfig, Error as TlsError};
+
+/// Re-export of the `rustls` version this crate is built against, so callers can
+/// build a [`ClientConfig`] without pinning a matching dependency themselves.
+pub use tokio_rustls::rustls;
+
+#[derive(Clone, Debug)]
+enum Endpoint {
+    Addr(SocketAddr),
+    Host(HostPort),
+}
+
+/// Connection context for [`StubbornTlsStream`]: where to connect, which server
+/// name to present (SNI) and verify the certificate against, and the shared
+/// rustls client configuration.
+#[derive(Clone)]
+pub struct TlsContext {
+    endpoint: Endpoint,
+    server_name: ServerName<'static>,
+    config: Arc<ClientConfig>,
+}
+
+impl TlsContext {
+    /// Connect to an already-resolved `addr` and verify the peer as `server_name`.
+    #[must_use]
+    pub const fn new(
+        addr: SocketAddr,
+        server_name: ServerName<'static>,
+        config: Arc<ClientConfig>,
+    ) -> Self {
+        Self {
+            endpoint: Endpoint::Addr(addr),
+            server_name,
+            config,
+        }
+    }
+
+    /// Connect to `host`, re-resolving it on every (re)connect exactly like
+    /// [`StubbornTcpHostStream`](crate::StubbornTcpHostStream), and verify the peer
+    /// as `server_name`.
+    #[must_use]
+    pub const fn with_host(
+        host: HostPort,
+        server_name: ServerName<'static>,
+        config: Arc<ClientConfig>,
+    ) -> Self {
+        Self {
+            endpoint: Endpoint::Host(host),
+            server_name,
+            config,
+        }
+    }
+
+    /// The server name presented via SNI and used for certificate verification.
+    #[must_use]
+    pub const fn server_name(&self) -> &ServerName<'static> {
+        &self.server_name
+    }
+}
+
+impl fmt::Debug for TlsContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsContext")
+            .field("endpoint", &self.endpoint)
+            .field("server_name", &self.server_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UnderlyingIo for TlsStream<TcpStream> {
+    type Context = TlsContext;
+
+    /// Connects TCP and performs the TLS handshake. Both steps are part of the
+    /// same attempt, so a handshake that stalls is bounded by
+    /// [`ReconnectOptions::with_connect_timeout`](crate::ReconnectOptions::with_connect_timeout).
+    fn establish(ctx: TlsContext) -> Pin<Box<dyn Future<Output = io::Result<Self>> + Send>> {
+        Box::pin(async move {
+            let tcp = match &ctx.endpoint {
+                Endpoint::Addr(addr) => TcpStream::connect(addr).await?,
+                Endpoint::Host(host) => host.connect().await?.0,
+            };
+            TlsConnector::from(ctx.config)
+                .connect(ctx.server_name, tcp)
+                .await
+        })
+    }
+
+    fn describe_context(ctx: &Self::Context) -> Option<String> {
+        Some(format!("{ctx:?}"))
+    }
+
+    /// The peer address when connecting to a host name; `None` for a
+    /// [`TlsContext::new`] endpoint, which already is the address.
+    fn resolved_addr(&self, ctx: &TlsContext) -> Option<SocketAddr> {
+        match ctx.endpoint {
+            Endpoint::Addr(_) => None,
+            Endpoint::Host(_) => self.get_ref().0.peer_addr().ok(),
+        }
+    }
+
+    /// Certificate validation failures and handshake rejections (locally
+    /// detected, or reported by the server through a fatal alert) are fatal:
+    /// retrying against the same server with the same configuration cannot
+    /// succeed. Everything else — refused or reset TCP connects, a peer hanging
+    /// up mid-handshake, timeouts — remains retryable.
+    fn is_fatal_connect_error(err: &io::Error) -> bool {
+        let Some(tls_err) = err.get_ref().and_then(|e| e.downcast_ref::<TlsError>()) else {
+            return false;
+        };
+        match tls_err {
+            TlsError::InvalidCertificate(_)
+            | TlsError::InvalidCertRevocationList(_)
+            | TlsError::NoCertificatesPresented
+            | TlsError::UnsupportedNameType
+            | TlsError::PeerIncompatible(_)
+            | TlsError::NoApplicationProtocol => true,
+            TlsError::AlertReceived(alert) => matches!(
+                alert,
+                AlertDescription::HandshakeFailure
+                    | AlertDescription::BadCertificate
+                    | AlertDescription::UnsupportedCertificate
+                    | AlertDescription::CertificateRevoked
+                    | AlertDescription::CertificateExpired
+                    | AlertDescription::CertificateUnknown
+                    | AlertDescription::CertificateRequired
+                    | AlertDescription::UnknownCA
+                    | AlertDescription::AccessDenied
+                    | AlertDescription::ProtocolVersion
+                    | AlertDescription::InsufficientSecurity
+                    | AlertDescription::NoApplicationProtocol
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// A reconnecting TLS client stream (rustls over TCP). Requires the `tls` feature.
+///
+/// Every (re)connect performs a fresh TCP connect and TLS handshake using the
+/// [`TlsContext`]. Handshake failures caused by the certificate or by the server
+/// rejecting the handshake are classified as fatal (see
+/// [`UnderlyingIo::is_fatal_connect_error`]), so a bad certificate ends in
+/// `Exhausted` instead of retrying forever.
+///
+/// ```no_run
+/// use sdre_stubborn_io::tokio::{StubbornTlsStream, TlsContext, rustls};
+/// use std::sync::Arc;
+/// use tokio::io::AsyncWriteExt;
+///
+/// # async fn example(config: rustls::ClientConfig) -> std::io::Result<()> {
+/// let server_name = rustls::pki_types::ServerName::try_from("feed.example.com").unwrap();
+/// let ctx = TlsContext::new("192.0.2.10:443".parse().unwrap(), server_name, Arc::new(config));
+/// let mut tls_stream = StubbornTlsStream::connect(ctx).await?;
+/// tls_stream.write_all(b"hello world!").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub type StubbornTlsStream = StubbornIo<TlsStream<TcpStream>>;
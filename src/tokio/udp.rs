@@ -0,0 +1,131 @@
+use super::io::{StubbornIo, UnderlyingIo, first_slice};
+use std::future::{Future, poll_fn};
+use std::io::{self, ErrorKind, IoSlice};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+use tokio::net::UdpSocket;
+
+impl UnderlyingIo for UdpSocket {
+    type Context = SocketAddr;
+
+    /// Binds a fresh ephemeral socket of the same address family as `addr` and
+    /// `connect()`s it, so that every (re)connect gets a new local port and a
+    /// clean kernel error queue.
+    fn establish(addr: SocketAddr) -> Pin<Box<dyn Future<Output = io::Result<Self>> + Send>> {
+        Box::pin(async move {
+            let local: SocketAddr = if addr.is_ipv4() {
+                (Ipv4Addr::UNSPECIFIED, 0).into()
+            } else {
+                (Ipv6Addr::UNSPECIFIED, 0).into()
+            };
+            let socket = Self::bind(local).await?;
+            socket.connect(addr).await?;
+            Ok(socket)
+        })
+    }
+
+    fn describe_context(ctx: &Self::Context) -> Option<String> {
+        Some(format!("{ctx:?}"))
+    }
+
+    /// UDP-specific disconnect classification. A connected UDP socket only learns
+    /// about a dead peer through ICMP (surfaced as `ConnectionRefused`, or
+    /// `ConnectionReset` on Windows) or through local routing failures; stream
+    /// termination kinds such as `BrokenPipe` and `UnexpectedEof` do not apply.
+    fn is_disconnect_error(&self, err: &io::Error) -> bool {
+        matches!(
+            err.kind(),
+            ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::NotConnected
+                | ErrorKind::AddrNotAvailable
+                | ErrorKind::HostUnreachable
+                | ErrorKind::NetworkUnreachable
+                | ErrorKind::NetworkDown
+        )
+    }
+
+    /// Zero-length datagrams are legal on UDP, so no read is ever final.
+    fn is_final_read(&self, _bytes_read: usize) -> bool {
+        false
+    }
+}
+
+/// A connected [`UdpSocket`](tokio::net::UdpSocket) that automatically rebinds and
+/// re-`connect()`s when the peer becomes unreachable.
+///
+/// Unlike [`StubbornTcpStream`](crate::StubbornTcpStream) this type does **not**
+/// implement `AsyncRead`/`AsyncWrite`: every [`send`](StubbornIo::send) is exactly one
+/// datagram and every [`recv`](StubbornIo::recv) returns exactly one datagram, so
+/// message boundaries are preserved across reconnects. Both go through the same
+/// [`ReconnectOptions`](crate::ReconnectOptions) machinery as the stream types,
+/// including [`WriteFailurePolicy`](crate::config::WriteFailurePolicy) for sends.
+///
+/// ```
+/// use sdre_stubborn_io::StubbornUdpSocket;
+/// use std::net::SocketAddr;
+///
+/// let addr: SocketAddr = "127.0.0.1:5550".parse().unwrap();
+///
+/// async {
+///     let mut socket = StubbornUdpSocket::connect(addr).await.unwrap();
+///     socket.send(b"{\"message\":\"hello\"}").await.unwrap();
+/// };
+/// ```
+pub type StubbornUdpSocket = StubbornIo<UdpSocket>;
+
+impl StubbornIo<UdpSocket> {
+    /// Attempts to send a single datagram to the connected peer.
+    ///
+    /// Disconnect handling follows the configured
+    /// [`WriteFailurePolicy`](crate::config::WriteFailurePolicy); a held datagram is
+    /// re-sent whole on the new socket once the reconnect completes. Under
+    /// `Replay`, each retained datagram is replayed as its own datagram.
+    pub fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_with(cx, &[IoSlice::new(buf)], |io, cx, bufs| {
+            io.poll_send(cx, first_slice(bufs))
+        })
+    }
+
+    /// Attempts to receive a single datagram from the connected peer into `buf`.
+    ///
+    /// An ICMP error queued on the socket only raises `EPOLLERR`, which tokio does
+    /// not report as read readiness. While no datagram is available the pending
+    /// socket error is therefore checked explicitly, so a refused peer is noticed
+    /// on the next `send` or on the next poll of `recv`, whichever comes first.
+    pub fn poll_recv(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.poll_read_with(cx, buf, |io, cx, buf| match io.poll_recv(cx, buf) {
+            Poll::Pending => match io.take_error() {
+                Ok(Some(err)) | Err(err) => Poll::Ready(Err(err)),
+                Ok(None) => Poll::Pending,
+            },
+            ready @ Poll::Ready(_) => ready,
+        })
+    }
+
+    /// Sends a single datagram to the connected peer, returning the number of
+    /// bytes sent.
+    pub async fn send(&mut self, buf: &[u8]) -> io::Result<usize> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_send(cx, buf)).await
+    }
+
+    /// Receives a single datagram from the connected peer, returning its length.
+    ///
+    /// As with [`UdpSocket::recv`](tokio::net::UdpSocket::recv), a datagram larger
+    /// than `buf` is truncated.
+    pub async fn recv(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read_buf = ReadBuf::new(buf);
+        poll_fn(|cx| Pin::new(&mut *self).poll_recv(cx, &mut read_buf)).await?;
+        Ok(read_buf.filled().len())
+    }
+}
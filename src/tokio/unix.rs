@@ -0,0 +1,57 @@
+use super::io::{StubbornIo, UnderlyingIo};
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::pin::Pin;
+use tokio::net::UnixStream;
+
+impl UnderlyingIo for UnixStream {
+    type Context = PathBuf;
+
+    fn establish(path: PathBuf) -> Pin<Box<dyn Future<Output = io::Result<Self>> + Send>> {
+        Box::pin(Self::connect(path))
+    }
+
+    fn describe_context(ctx: &Self::Context) -> Option<String> {
+        Some(ctx.display().to_string())
+    }
+
+    /// Unix-socket-specific disconnect classification. On top of the stream
+    /// termination kinds shared with TCP, `NotFound` (the socket file was removed,
+    /// e.g. while the listening container restarts) and `ConnectionRefused` (the
+    /// file exists but nobody is listening on it yet) are treated as retryable
+    /// disconnects rather than fatal errors. Network-path kinds are dropped since
+    /// a local socket never routes.
+    fn is_disconnect_error(&self, err: &io::Error) -> bool {
+        matches!(
+            err.kind(),
+            ErrorKind::NotFound
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::BrokenPipe
+                | ErrorKind::TimedOut
+        )
+    }
+}
+
+/// A drop in replacement for tokio's [`UnixStream`](tokio::net::UnixStream), with the
+/// distinction that it will automatically attempt to reconnect in the face of connectivity failures.
+///
+/// This includes the socket file itself disappearing and later being recreated by a
+/// restarting listener.
+///
+/// ```
+/// use sdre_stubborn_io::StubbornUnixStream;
+/// use std::path::PathBuf;
+/// use tokio::io::AsyncWriteExt;
+///
+/// let path = PathBuf::from("/run/acars/decoder.sock");
+///
+/// async {
+///     let mut unix_stream = StubbornUnixStream::connect(path).await.unwrap();
+///     unix_stream.write_all(b"hello world!").await.unwrap();
+/// };
+/// ```
+pub type StubbornUnixStream = StubbornIo<UnixStream>;
@@ -34,6 +34,9 @@ pub type ReadScript = Vec<(Poll<io::Result<()>>, Vec<u8>)>;
 /// successful write of `buf.len()` bytes".
 pub type WriteScript = Vec<Option<Poll<io::Result<usize>>>>;
 
+/// Bytes accepted by each established connection, in connection order.
+pub type WriteLog = Arc<Mutex<Vec<Vec<u8>>>>;
+
 /// What an `establish` call should do.
 #[derive(Clone, Debug)]
 pub enum Outcome {
@@ -51,6 +54,7 @@ pub struct DummyCtor {
     pub read_script: Arc<Mutex<ReadScript>>,
     pub write_script: Arc<Mutex<WriteScript>>,
     pub establish_calls: Arc<std::sync::atomic::AtomicUsize>,
+    pub written: WriteLog,
 }
 
 impl DummyCtor {
@@ -61,6 +65,7 @@ impl DummyCtor {
             read_script: Arc::new(Mutex::new(Vec::new())),
             write_script: Arc::new(Mutex::new(Vec::new())),
             establish_calls: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            written: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -80,11 +85,32 @@ impl DummyCtor {
         self.establish_calls
             .load(std::sync::atomic::Ordering::Relaxed)
     }
+
+    /// Snapshot of the bytes each successful connection accepted.
+    pub fn written(&self) -> Vec<Vec<u8>> {
+        self.written.lock().unwrap().clone()
+    }
 }
 
 pub struct DummyIo {
     pub read_script: Arc<Mutex<ReadScript>>,
     pub write_script: Arc<Mutex<WriteScript>>,
+    pub written: WriteLog,
+    /// Index of this connection's entry in `written`.
+    pub conn: usize,
+}
+
+impl DummyIo {
+    pub fn new(ctor: &DummyCtor) -> Self {
+        let mut written = ctor.written.lock().unwrap();
+        written.push(Vec::new());
+        DummyIo {
+            read_script: ctor.read_script.clone(),
+            write_script: ctor.write_script.clone(),
+            written: ctor.written.clone(),
+            conn: written.len() - 1,
+        }
+    }
 }
 
 impl UnderlyingIo for DummyIo {
@@ -101,25 +127,23 @@ impl UnderlyingIo for DummyIo {
                 outcomes.remove(0)
             }
         };
-        let read_script = ctor.read_script.clone();
-        let write_script = ctor.write_script.clone();
         Box::pin(async move {
             match outcome {
-                Outcome::Ok => Ok(DummyIo {
-                    read_script,
-                    write_script,
-                }),
+                Outcome::Ok => Ok(DummyIo::new(&ctor)),
                 Outcome::Err(kind) => Err(io::Error::new(kind, "dummy: scripted failure")),
                 Outcome::SlowOk(d) => {
                     tokio::time::sleep(d).await;
-                    Ok(DummyIo {
-                        read_script,
-                        write_script,
-                    })
+                    Ok(DummyIo::new(&ctor))
                 }
             }
         })
     }
+
+    /// `PermissionDenied` stands in for errors retrying cannot fix (e.g. a TLS
+    /// certificate rejection).
+    fn is_fatal_connect_error(err: &io::Error) -> bool {
+        err.kind() == ErrorKind::PermissionDenied
+    }
 }
 
 impl AsyncRead for DummyIo {
@@ -156,13 +180,14 @@ impl AsyncWrite for DummyIo {
     ) -> Poll<io::Result<usize>> {
         let cloned = self.write_script.clone();
         let mut script = cloned.lock().unwrap();
-        if script.is_empty() {
-            return Poll::Ready(Ok(buf.len()));
-        }
-        match script.remove(0) {
+        let result = match (!script.is_empty()).then(|| script.remove(0)).flatten() {
             Some(p) => p,
             None => Poll::Ready(Ok(buf.len())),
+        };
+        if let Poll::Ready(Ok(n)) = &result {
+            self.written.lock().unwrap()[self.conn].extend_from_slice(&buf[..*n]);
         }
+        result
     }
 
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -0,0 +1,114 @@
+//! Frame atomicity of `StubbornFramed` across reconnects, driven through the
+//! in-memory `DummyIo` shim. Only built with `--features codec`.
+#![cfg(feature = "codec")]
+#![allow(missing_docs, clippy::missing_panics_doc)]
+
+mod common;
+
+use common::{DummyCtor, DummyIo, Outcome};
+use futures::{SinkExt, StreamExt};
+use sdre_stubborn_io::ReconnectOptions;
+use sdre_stubborn_io::config::WriteFailurePolicy;
+use sdre_stubborn_io::tokio::{StubbornFramed, StubbornIo};
+use std::io::{self, ErrorKind};
+use std::task::Poll;
+use std::time::Duration;
+use tokio_util::codec::LinesCodec;
+
+fn fast_retries(n: usize) -> impl Fn() -> Vec<Duration> + Send + Sync + 'static {
+    move || vec![Duration::from_millis(5); n]
+}
+
+async fn framed(ctor: DummyCtor) -> StubbornFramed<DummyIo, LinesCodec> {
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(2));
+    let io = StubbornIo::<DummyIo>::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    StubbornFramed::new(io, LinesCodec::new())
+}
+
+#[tokio::test]
+async fn partially_written_frame_restarts_on_new_connection() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![
+        None,
+        Some(Poll::Ready(Ok(4))),
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::BrokenPipe,
+            "peer gone",
+        )))),
+    ]);
+    let mut lines = framed(ctor.clone()).await;
+
+    lines.send("first").await.unwrap();
+    lines.send("{\"a\":1}").await.unwrap();
+
+    assert_eq!(
+        ctor.written(),
+        vec![b"first\n{\"a\"".to_vec(), b"{\"a\":1}\n".to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn restarted_frame_is_not_also_replayed() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![
+        None,
+        Some(Poll::Ready(Ok(4))),
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::BrokenPipe,
+            "peer gone",
+        )))),
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(2))
+        .with_write_failure_policy(WriteFailurePolicy::Replay { capacity: 64 });
+    let io = StubbornIo::<DummyIo>::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let mut lines = StubbornFramed::new(io, LinesCodec::new());
+
+    lines.send("first").await.unwrap();
+    lines.send("{\"a\":1}").await.unwrap();
+
+    // The new connection gets the replayed whole frame, then the restarted
+    // one, without the head the old connection accepted in between.
+    assert_eq!(
+        ctor.written(),
+        vec![b"first\n{\"a\"".to_vec(), b"first\n{\"a\":1}\n".to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn partial_inbound_frame_is_discarded_on_reconnect() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![
+        (Poll::Ready(Ok(())), b"one\n{\"trunc".to_vec()),
+        (
+            Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+            Vec::new(),
+        ),
+        (Poll::Ready(Ok(())), b"two\n".to_vec()),
+    ]);
+    let mut lines = framed(ctor.clone()).await;
+
+    assert_eq!(lines.next().await.unwrap().unwrap(), "one");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "two");
+    assert_eq!(ctor.establish_count(), 2);
+}
+
+#[tokio::test]
+async fn complete_frames_buffered_before_disconnect_are_delivered() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![
+        (Poll::Ready(Ok(())), b"a\nb\nc".to_vec()),
+        (
+            Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+            Vec::new(),
+        ),
+        (Poll::Ready(Ok(())), b"d\n".to_vec()),
+    ]);
+    let mut lines = framed(ctor).await;
+
+    let mut got = Vec::new();
+    for _ in 0..3 {
+        got.push(lines.next().await.unwrap().unwrap());
+    }
+    assert_eq!(got, ["a", "b", "d"]);
+}
@@ -1,12 +1,25 @@
-//! Real-socket smoke test that exercises `poll_shutdown -> Closed` against an
-//! actual `TcpStream`. Complements the deterministic in-memory suites in
-//! `state_machine.rs` and `property_tests.rs`.
+//! Real-socket smoke tests: `poll_shutdown -> Closed` against an actual
+//! `TcpStream`, DNS re-resolution for `StubbornTcpHostStream`, datagram
+//! behaviour of `StubbornUdpSocket`, socket-file recreation for
+//! `StubbornUnixStream`, and the read idle timeout. Complements the
+//! deterministic in-memory suites in `state_machine.rs` and `property_tests.rs`.
 #![allow(missing_docs, clippy::collection_is_never_read)]
 
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use sdre_stubborn_io::StubbornTcpStream;
-use tokio::{io::AsyncWriteExt, sync::oneshot};
+use sdre_stubborn_io::config::ReconnectEvent;
+use sdre_stubborn_io::tokio::{HostPort, Resolver};
+use sdre_stubborn_io::{
+    ReconnectOptions, StubbornTcpHostStream, StubbornTcpStream, StubbornUdpSocket,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::oneshot;
 
 #[tokio::test]
 async fn back_to_back_shutdown_attempts() {
@@ -31,3 +44,250 @@ async fn back_to_back_shutdown_attempts() {
     let error = result.unwrap_err();
     assert_eq!(error.kind(), std::io::ErrorKind::NotConnected);
 }
+
+/// Resolver whose answer can be swapped at runtime, standing in for DNS.
+#[derive(Clone, Default)]
+struct FakeResolver {
+    answer: Arc<Mutex<Vec<SocketAddr>>>,
+    lookups: Arc<AtomicUsize>,
+}
+
+impl FakeResolver {
+    fn set(&self, addrs: Vec<SocketAddr>) {
+        *self.answer.lock().unwrap() = addrs;
+    }
+}
+
+impl Resolver for FakeResolver {
+    fn resolve(
+        &self,
+        host: &str,
+        _port: u16,
+    ) -> Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send>> {
+        assert_eq!(host, "feed.invalid");
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+        let addrs = self.answer.lock().unwrap().clone();
+        Box::pin(async move { Ok(addrs) })
+    }
+}
+
+/// Address on loopback that refuses connections.
+async fn closed_tcp_addr() -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[tokio::test]
+async fn host_stream_tries_addresses_in_order_and_re_resolves() {
+    let first = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let second = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let first_addr = first.local_addr().unwrap();
+    let second_addr = second.local_addr().unwrap();
+    let dead = closed_tcp_addr().await;
+
+    let resolver = FakeResolver::default();
+    resolver.set(vec![dead, first_addr]);
+
+    let selected = Arc::new(Mutex::new(Vec::new()));
+    let log = selected.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(5); 3])
+        .with_event_callback(move |ev| {
+            if let ReconnectEvent::AddressResolved { attempt, addr } = ev {
+                log.lock().unwrap().push((attempt, addr));
+            }
+        });
+    let ctx = HostPort::new("feed.invalid", 0).with_resolver(resolver.clone());
+    let mut stream = StubbornTcpHostStream::connect_with_options(ctx, opts)
+        .await
+        .unwrap();
+    assert_eq!(stream.resolved_addr(), first_addr);
+
+    // The feeder's DNS target moves; the old upstream hangs up.
+    resolver.set(vec![second_addr]);
+    let (conn, _) = first.accept().await.unwrap();
+    drop(conn);
+    let server = tokio::spawn(async move {
+        let (mut conn, _) = second.accept().await.unwrap();
+        conn.write_all(b"moved").await.unwrap();
+    });
+
+    let mut buf = [0u8; 5];
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buf, b"moved");
+    assert_eq!(stream.peer_addr().unwrap(), second_addr);
+    assert_eq!(resolver.lookups.load(Ordering::Relaxed), 2);
+    assert_eq!(
+        *selected.lock().unwrap(),
+        vec![(0, first_addr), (1, second_addr)]
+    );
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn host_stream_with_empty_resolution_fails() {
+    let opts = ReconnectOptions::new().with_exit_if_first_connect_fails(true);
+    let ctx = HostPort::new("feed.invalid", 5550).with_resolver(FakeResolver::default());
+    let err = StubbornTcpHostStream::connect_with_options(ctx, opts)
+        .await
+        .err()
+        .expect("expected connect without addresses to fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn udp_preserves_datagram_boundaries() {
+    let server = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    let mut socket = StubbornUdpSocket::connect(server_addr).await.unwrap();
+
+    socket.send(b"one").await.unwrap();
+    socket.send(b"three").await.unwrap();
+
+    let mut buf = [0u8; 64];
+    let (n, client_addr) = server.recv_from(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"one");
+    let (n, _) = server.recv_from(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"three");
+
+    server.send_to(b"ab", client_addr).await.unwrap();
+    server.send_to(b"cdef", client_addr).await.unwrap();
+    let n = socket.recv(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"ab");
+    let n = socket.recv(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"cdef");
+}
+
+#[tokio::test]
+async fn udp_reconnects_after_connection_refused() {
+    // Reserve a port, then close it so the first datagram bounces with ICMP
+    // port-unreachable.
+    let server = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_addr = server.local_addr().unwrap();
+    drop(server);
+
+    let reconnected = Arc::new(AtomicUsize::new(0));
+    let rc = reconnected.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(5); 3])
+        .with_event_callback(move |ev| {
+            if matches!(ev, ReconnectEvent::Connected { attempt } if attempt > 0) {
+                rc.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    let mut socket = StubbornUdpSocket::connect_with_options(server_addr, opts)
+        .await
+        .unwrap();
+    let first_local = socket.local_addr().unwrap();
+
+    socket.send(b"into the void").await.unwrap();
+    // Give the loopback ICMP port-unreachable time to land on the socket.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut buf = [0u8; 64];
+    // The refused datagram surfaces on the next recv, which triggers a rebind;
+    // afterwards the recv stays pending because nobody is listening.
+    let pending = tokio::time::timeout(Duration::from_millis(200), socket.recv(&mut buf)).await;
+    assert!(pending.is_err());
+    assert_eq!(reconnected.load(Ordering::Relaxed), 1);
+    assert!(socket.is_connected());
+    assert_ne!(socket.local_addr().unwrap(), first_local);
+
+    let server = tokio::net::UdpSocket::bind(server_addr).await.unwrap();
+    socket.send(b"back").await.unwrap();
+    let n = server.recv(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"back");
+}
+
+#[tokio::test]
+async fn read_idle_timeout_reconnects_silent_peer_only() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        // Steady traffic, well inside the idle window...
+        let (mut first, _) = listener.accept().await.unwrap();
+        for _ in 0..5 {
+            first.write_all(b".").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(40)).await;
+        }
+        // ...then silence without closing, like a peer behind an expired NAT
+        // entry. Only a new connection gets data.
+        let (mut second, _) = listener.accept().await.unwrap();
+        second.write_all(b"again").await.unwrap();
+        (first, second)
+    });
+
+    let idle_timeouts = Arc::new(AtomicUsize::new(0));
+    let it = idle_timeouts.clone();
+    let opts = ReconnectOptions::new()
+        .with_read_idle_timeout(Duration::from_millis(150))
+        .with_retries_generator(|| vec![Duration::from_millis(5); 3])
+        .with_event_callback(move |ev| {
+            if matches!(ev, ReconnectEvent::IdleTimeout) {
+                it.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    let mut stream = StubbornTcpStream::connect_with_options(addr, opts)
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b".....");
+    assert_eq!(idle_timeouts.load(Ordering::Relaxed), 0);
+
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buf, b"again");
+    assert_eq!(idle_timeouts.load(Ordering::Relaxed), 1);
+    drop(server.await.unwrap());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_reconnects_after_socket_file_is_recreated() {
+    use sdre_stubborn_io::StubbornUnixStream;
+    use tokio::net::UnixListener;
+
+    let path = std::env::temp_dir().join(format!("stubborn-io-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let server_path = path.clone();
+    let server = tokio::spawn(async move {
+        let (mut conn, _) = listener.accept().await.unwrap();
+        conn.write_all(b"first").await.unwrap();
+        // Simulate the decoder container going away: connection, listener and
+        // socket file all disappear for a while.
+        drop(conn);
+        drop(listener);
+        std::fs::remove_file(&server_path).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let listener = UnixListener::bind(&server_path).unwrap();
+        let (mut conn, _) = listener.accept().await.unwrap();
+        conn.write_all(b"second").await.unwrap();
+        std::fs::remove_file(&server_path).unwrap();
+    });
+
+    let opts =
+        ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(20); 50]);
+    let mut stream = StubbornUnixStream::connect_with_options(path, opts)
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"first");
+    let mut buf = [0u8; 6];
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buf, b"second");
+    server.await.unwrap();
+}
@@ -0,0 +1,86 @@
+//! `OpenMetrics` rendering of connection statistics, driven through the
+//! in-memory `DummyIo` shim. Only built with `--features metrics`.
+#![cfg(feature = "metrics")]
+#![allow(missing_docs, clippy::missing_panics_doc)]
+
+mod common;
+
+use common::{DummyCtor, DummyIo, Outcome};
+use sdre_stubborn_io::ReconnectOptions;
+use sdre_stubborn_io::metrics::MetricsRegistry;
+use sdre_stubborn_io::tokio::StubbornIo;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+fn fast_retries(n: usize) -> impl Fn() -> Vec<Duration> + Send + Sync + 'static {
+    move || vec![Duration::from_millis(5); n]
+}
+
+#[tokio::test]
+async fn renders_every_family_for_a_registered_stream() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Ok,
+        Outcome::Ok,
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_connection_name("feed")
+        .with_retries_generator(fast_retries(2));
+    let mut s = StubbornIo::<DummyIo>::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    s.force_reconnect();
+    tokio::io::AsyncWriteExt::write_all(&mut s, b"x")
+        .await
+        .unwrap();
+    let registry = MetricsRegistry::new();
+    registry.register(&s);
+
+    let text = registry.render();
+
+    for line in [
+        "# TYPE stubborn_io_connected gauge",
+        "stubborn_io_connected{connection=\"feed\"} 1",
+        "# TYPE stubborn_io_reconnects counter",
+        "stubborn_io_reconnects_total{connection=\"feed\"} 1",
+        "stubborn_io_connect_failures_total{connection=\"feed\",kind=\"ConnectionRefused\"} 1",
+        "stubborn_io_dropped_bytes_total{connection=\"feed\"} 0",
+        "# TYPE stubborn_io_disconnect_duration_seconds histogram",
+        "# UNIT stubborn_io_disconnect_duration_seconds seconds",
+        "stubborn_io_disconnect_duration_seconds_bucket{connection=\"feed\",le=\"0.1\"} 1",
+        "stubborn_io_disconnect_duration_seconds_bucket{connection=\"feed\",le=\"1800.0\"} 1",
+        "stubborn_io_disconnect_duration_seconds_bucket{connection=\"feed\",le=\"+Inf\"} 1",
+        "stubborn_io_disconnect_duration_seconds_count{connection=\"feed\"} 1",
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {line:?} in:\n{text}"
+        );
+    }
+    assert!(text.ends_with("# EOF\n"));
+}
+
+#[tokio::test]
+async fn dropped_streams_disappear_and_names_are_escaped() {
+    let registry = MetricsRegistry::new();
+    let kept = StubbornIo::<DummyIo>::connect_with_options(
+        DummyCtor::new(vec![Outcome::Ok]),
+        ReconnectOptions::new().with_connection_name("a \"quoted\" name"),
+    )
+    .await
+    .unwrap();
+    let dropped = StubbornIo::<DummyIo>::connect_with_options(
+        DummyCtor::new(vec![Outcome::Ok]),
+        ReconnectOptions::new().with_connection_name("gone"),
+    )
+    .await
+    .unwrap();
+    registry.clone().register(&kept);
+    registry.register(&dropped);
+    drop(dropped);
+
+    let text = registry.render();
+
+    assert!(text.contains("stubborn_io_connected{connection=\"a \\\"quoted\\\" name\"} 1"));
+    assert!(!text.contains("gone"));
+}
@@ -10,9 +10,13 @@
 mod common;
 
 use common::{DummyCtor, DummyIo, Outcome};
+use futures::StreamExt;
 use sdre_stubborn_io::ReconnectOptions;
-use sdre_stubborn_io::config::{ReconnectEvent, WriteFailurePolicy};
-use sdre_stubborn_io::tokio::{StubbornIo, UnderlyingIo};
+use sdre_stubborn_io::config::{
+    DisconnectReason, OwnedEventKind, OwnedReconnectEvent, ReconnectEvent, RetryDecision,
+    WriteFailurePolicy,
+};
+use sdre_stubborn_io::tokio::{ConnectionState, StubbornIo, UnderlyingIo};
 use std::io::{self, ErrorKind, IoSlice};
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -108,7 +112,71 @@ async fn initial_connect_failure_exhausts_and_errors() {
         .err()
         .expect("expected exhausted initial connect to fail");
     assert_eq!(err.kind(), ErrorKind::ConnectionRefused);
-    assert!(log.lock().unwrap().iter().any(|e| e.contains("Exhausted")));
+    assert_eq!(
+        log.lock().unwrap().last().unwrap(),
+        "Exhausted { reason: RetriesExhausted }"
+    );
+}
+
+#[tokio::test]
+async fn connect_lazy_returns_disconnected_and_first_write_connects() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Ok,
+    ]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_lazy(ctor.clone(), opts);
+    assert!(!s.is_connected());
+    assert!(!s.is_terminated());
+    assert_eq!(ctor.establish_count(), 0);
+
+    s.write_all(b"x").await.unwrap();
+
+    assert!(s.is_connected());
+    assert_eq!(ctor.written(), vec![b"x".to_vec()]);
+    let log = log.lock().unwrap();
+    assert!(log[0].starts_with("ConnectFailed"), "{log:?}");
+    assert!(log[0].ends_with("attempt: 0 }"), "{log:?}");
+    assert_eq!(
+        log[1..],
+        [
+            "ReconnectScheduled { attempt: 1, delay: 5ms }",
+            "Connected { attempt: 1 }",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn connect_lazy_has_no_underlying_io_until_connected() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let mut s = StubbornDummy::connect_lazy(ctor, ReconnectOptions::new());
+
+    assert!(s.get_ref().is_none());
+    assert!(s.get_mut().is_none());
+    s.write_all(b"x").await.unwrap();
+    assert!(s.get_ref().is_some());
+}
+
+#[tokio::test]
+async fn connect_lazy_with_exit_on_first_fail_terminates_on_first_poll() {
+    let ctor = DummyCtor::new(vec![Outcome::Err(ErrorKind::ConnectionRefused)]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_exit_if_first_connect_fails(true)
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_lazy(ctor, opts);
+
+    let err = s.read(&mut [0u8; 1]).await.unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(s.is_terminated());
+    assert_eq!(
+        log.lock().unwrap().last().unwrap(),
+        "Exhausted { reason: FirstConnectFailed }"
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -132,7 +200,12 @@ async fn fatal_read_error_triggers_reconnect() {
     let opts = ReconnectOptions::new()
         .with_retries_generator(fast_retries(3))
         .with_event_callback(move |ev| {
-            if matches!(ev, ReconnectEvent::Disconnected) {
+            if matches!(
+                ev,
+                ReconnectEvent::Disconnected {
+                    reason: DisconnectReason::IoError(ErrorKind::ConnectionAborted)
+                }
+            ) {
                 dc.fetch_add(1, Ordering::Relaxed);
             }
         });
@@ -180,6 +253,170 @@ async fn eof_zero_byte_read_triggers_reconnect() {
     assert_eq!(ctor.establish_count(), 2);
 }
 
+#[tokio::test]
+async fn read_idle_timeout_emits_event_and_reconnects() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_read_idle_timeout(Duration::from_millis(30))
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    // The dummy never yields data; read in short slices until the reconnect
+    // is reported, well before the second idle window ends.
+    let reconnected = || {
+        log.lock()
+            .unwrap()
+            .iter()
+            .any(|e| e == "Connected { attempt: 1 }")
+    };
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !reconnected() {
+            let _ = tokio::time::timeout(Duration::from_millis(5), s.read(&mut [0u8; 8])).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(ctor.establish_count(), 2);
+    assert_eq!(
+        log.lock().unwrap()[..5],
+        [
+            "Connected { attempt: 0 }",
+            "IdleTimeout",
+            "Disconnected { reason: IdleTimeout }",
+            "ReconnectScheduled { attempt: 1, delay: 5ms }",
+            "Connected { attempt: 1 }",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unrepresentable_read_idle_timeout_never_fires() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok])
+        .with_read_script(vec![(Poll::Ready(Ok(())), b"a".to_vec())]);
+    let opts = ReconnectOptions::new().with_read_idle_timeout(Duration::MAX);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 1];
+    assert_eq!(s.read(&mut buf).await.unwrap(), 1);
+    let idle = tokio::time::timeout(Duration::from_millis(20), s.read(&mut buf)).await;
+
+    assert!(idle.is_err());
+    assert!(s.is_connected());
+    assert_eq!(ctor.establish_count(), 1);
+}
+
+#[tokio::test]
+async fn force_reconnect_replaces_connection_through_retry_schedule() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Ok,
+        Outcome::SlowOk(Duration::from_millis(200)),
+        Outcome::Ok,
+    ]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_connect_timeout(Some(Duration::from_millis(20)))
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    s.write_all(b"a").await.unwrap();
+
+    s.force_reconnect();
+    assert!(!s.is_connected());
+    s.write_all(b"b").await.unwrap();
+
+    assert_eq!(ctor.written(), vec![b"a".to_vec(), b"b".to_vec()]);
+    let log = log.lock().unwrap();
+    assert_eq!(log[1], "Disconnected { reason: Forced }");
+    assert_eq!(log[2], "ReconnectScheduled { attempt: 1, delay: 5ms }");
+    assert!(log[3].starts_with("ConnectFailed"), "{log:?}");
+    assert_eq!(log[5], "Connected { attempt: 2 }");
+}
+
+#[tokio::test]
+async fn force_reconnect_is_a_no_op_unless_connected() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    s.force_reconnect();
+    s.force_reconnect();
+    s.write_all(b"x").await.unwrap();
+
+    assert_eq!(ctor.establish_count(), 2);
+    let disconnects = log
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.starts_with("Disconnected"))
+        .count();
+    assert_eq!(disconnects, 1);
+}
+
+#[tokio::test]
+async fn set_context_applies_from_the_next_reconnect() {
+    let old = DummyCtor::new(vec![Outcome::Ok]).with_write_script(vec![
+        None,
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::BrokenPipe,
+            "peer gone",
+        )))),
+    ]);
+    let new = DummyCtor::new(vec![Outcome::Ok]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(1));
+    let mut s = StubbornDummy::connect_with_options(old.clone(), opts)
+        .await
+        .unwrap();
+
+    s.set_context(new.clone());
+    assert!(s.is_connected());
+    s.write_all(b"a").await.unwrap();
+    s.write_all(b"b").await.unwrap();
+
+    assert_eq!(old.written(), vec![b"a".to_vec()]);
+    assert_eq!(new.written(), vec![b"b".to_vec()]);
+}
+
+#[tokio::test]
+async fn retarget_reconnects_to_the_new_context_right_away() {
+    let old = DummyCtor::new(vec![Outcome::Ok]);
+    let new = DummyCtor::new(vec![Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(old.clone(), opts)
+        .await
+        .unwrap();
+
+    s.retarget(new.clone());
+    s.write_all(b"x").await.unwrap();
+
+    assert_eq!(old.establish_count(), 1);
+    assert_eq!(new.written(), vec![b"x".to_vec()]);
+    assert_eq!(
+        log.lock().unwrap()[1..],
+        [
+            "ContextChanged",
+            "Disconnected { reason: ContextChanged }",
+            "ReconnectScheduled { attempt: 1, delay: 5ms }",
+            "Connected { attempt: 1 }",
+        ]
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Exhaustion → terminal
 // ---------------------------------------------------------------------------
@@ -210,6 +447,48 @@ async fn read_after_exhaustion_returns_not_connected() {
     assert!(!s.is_closed());
 }
 
+#[tokio::test]
+async fn fatal_reconnect_error_is_terminal_without_further_retries() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Ok,
+        Outcome::Err(ErrorKind::PermissionDenied),
+        Outcome::Ok,
+    ])
+    .with_read_script(vec![(
+        Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "boom"))),
+        vec![],
+    )]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(5))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let mut buf = [0u8; 1];
+    let err = s.read_exact(&mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(s.is_terminated());
+    assert_eq!(ctor.establish_count(), 2);
+    let events = log.lock().unwrap();
+    assert_eq!(
+        events.last().map(String::as_str),
+        Some("Exhausted { reason: FatalError }")
+    );
+}
+
+#[tokio::test]
+async fn fatal_initial_connect_error_bails_without_retries() {
+    let ctor = DummyCtor::new(vec![Outcome::Err(ErrorKind::PermissionDenied), Outcome::Ok]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(5));
+    let err = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .err()
+        .expect("expected fatal initial connect to fail");
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(ctor.establish_count(), 1);
+}
+
 // ---------------------------------------------------------------------------
 // Explicit shutdown → Closed
 // ---------------------------------------------------------------------------
@@ -288,118 +567,1065 @@ async fn connect_timeout_elapses_and_retries() {
 }
 
 // ---------------------------------------------------------------------------
-// Write failure policy
+// Stability window
 // ---------------------------------------------------------------------------
 
+fn scheduled_delays(log: &Mutex<Vec<String>>) -> Vec<String> {
+    log.lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.starts_with("ReconnectScheduled"))
+        .cloned()
+        .collect()
+}
+
 #[tokio::test]
-async fn drop_and_notify_returns_buf_len_and_emits_event() {
-    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
-        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
-    )]);
-    let dropped = Arc::new(AtomicUsize::new(0));
-    let dc = dropped.clone();
+async fn short_lived_connections_continue_the_backoff() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok; 3]);
+    let (log, cb) = event_sink();
     let opts = ReconnectOptions::new()
-        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
-        .with_retries_generator(fast_retries(2))
-        .with_event_callback(move |ev| {
-            if let ReconnectEvent::WriteWhileDisconnected { bytes_dropped } = ev {
-                dc.fetch_add(bytes_dropped, Ordering::Relaxed);
-            }
-        });
-    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .with_retries_generator(|| (1..=2).map(Duration::from_millis))
+        .with_stability_window(Duration::from_secs(3600))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
         .await
         .unwrap();
-    let n = s.write(b"hello").await.unwrap();
-    assert_eq!(n, 5);
-    assert_eq!(dropped.load(Ordering::Relaxed), 5);
+
+    for _ in 0..2 {
+        s.force_reconnect();
+        s.write_all(b"x").await.unwrap();
+    }
+    s.force_reconnect();
+    assert!(s.write_all(b"x").await.is_err());
+
+    assert!(s.is_terminated());
+    assert_eq!(ctor.establish_count(), 3);
+    assert_eq!(
+        scheduled_delays(&log),
+        vec![
+            "ReconnectScheduled { attempt: 1, delay: 1ms }",
+            "ReconnectScheduled { attempt: 1, delay: 2ms }",
+        ]
+    );
 }
 
 #[tokio::test]
-async fn drop_and_notify_vectored_returns_sum_of_lengths() {
-    // Connected-path vectored write that reveals a disconnect via the underlying
-    // poll. The crate-level `poll_write_vectored` lives over `StubbornIo`, but
-    // tokio's default impl falls back to `poll_write` for non-vectored sinks,
-    // so we exercise the same DropAndNotify accounting via a sequence of writes.
-    let bufs = [b"hello".as_slice(), b" ".as_slice(), b"world".as_slice()];
-    let total: usize = bufs.iter().map(|b| b.len()).sum();
-    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
-        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
-    )]);
-    let dropped = Arc::new(AtomicUsize::new(0));
-    let dc = dropped.clone();
+async fn stable_connection_starts_the_backoff_over() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok; 3]);
+    let (log, cb) = event_sink();
     let opts = ReconnectOptions::new()
-        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
-        .with_retries_generator(fast_retries(2))
-        .with_event_callback(move |ev| {
-            if let ReconnectEvent::WriteWhileDisconnected { bytes_dropped } = ev {
-                dc.fetch_add(bytes_dropped, Ordering::Relaxed);
-            }
-        });
-    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .with_retries_generator(|| (1..=2).map(Duration::from_millis))
+        .with_stability_window(Duration::from_millis(20))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
         .await
         .unwrap();
-    // Use the vectored entry point directly via the AsyncWriteExt::write_vectored.
-    let slices: Vec<IoSlice<'_>> = bufs.iter().map(|b| IoSlice::new(b)).collect();
-    let n = s.write_vectored(&slices).await.unwrap();
-    assert_eq!(n, total);
-    assert_eq!(dropped.load(Ordering::Relaxed), total);
-}
 
-// ---------------------------------------------------------------------------
-// Disconnect-kind defaults
-// ---------------------------------------------------------------------------
-
-#[test]
-fn default_is_disconnect_error_covers_canonical_set() {
-    let io_dummy = DummyIo {
-        read_script: Arc::new(Mutex::new(Vec::new())),
-        write_script: Arc::new(Mutex::new(Vec::new())),
-    };
-    let disconnect_kinds = [
-        ErrorKind::ConnectionRefused,
-        ErrorKind::ConnectionReset,
-        ErrorKind::ConnectionAborted,
-        ErrorKind::NotConnected,
-        ErrorKind::AddrInUse,
-        ErrorKind::AddrNotAvailable,
-        ErrorKind::BrokenPipe,
-        ErrorKind::TimedOut,
-        ErrorKind::UnexpectedEof,
-        ErrorKind::HostUnreachable,
-        ErrorKind::NetworkUnreachable,
-        ErrorKind::NetworkDown,
-    ];
-    for kind in disconnect_kinds {
-        let err = io::Error::new(kind, "x");
-        assert!(io_dummy.is_disconnect_error(&err), "should match: {kind:?}");
-    }
-    let benign = [
-        ErrorKind::WouldBlock,
-        ErrorKind::Interrupted,
-        ErrorKind::Other,
-        ErrorKind::InvalidData,
-    ];
-    for kind in benign {
-        let err = io::Error::new(kind, "x");
-        assert!(
-            !io_dummy.is_disconnect_error(&err),
-            "should NOT match: {kind:?}"
-        );
+    for _ in 0..2 {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        s.force_reconnect();
+        s.write_all(b"x").await.unwrap();
     }
+
+    assert_eq!(
+        scheduled_delays(&log),
+        vec![
+            "ReconnectScheduled { attempt: 1, delay: 1ms }",
+            "ReconnectScheduled { attempt: 1, delay: 1ms }",
+        ]
+    );
 }
 
-#[test]
-fn tcp_overrides_drop_unexpected_eof() {
-    use tokio::net::TcpStream;
-    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap();
-    let rt = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
+#[tokio::test(start_paused = true)]
+async fn stability_is_measured_from_establishment_not_pickup() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok; 3]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| (1..=2).map(Duration::from_millis))
+        .with_stability_window(Duration::from_millis(20))
+        .with_background_reconnect::<DummyIo>(true)
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
         .unwrap();
-    let tcp = rt.block_on(async move { TcpStream::connect(addr).await.unwrap() });
-    let err = io::Error::new(ErrorKind::UnexpectedEof, "x");
-    assert!(!tcp.is_disconnect_error(&err));
+
+    s.force_reconnect();
+    // The task connects after 1ms; the stream picks it up 30ms later.
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(ctor.establish_count(), 2);
+    s.write_all(b"x").await.unwrap();
+    s.force_reconnect();
+
+    assert_eq!(
+        scheduled_delays(&log),
+        vec![
+            "ReconnectScheduled { attempt: 1, delay: 1ms }",
+            "ReconnectScheduled { attempt: 1, delay: 1ms }",
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Retry policy
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn retry_policy_picks_delays_by_error_kind() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Err(ErrorKind::TimedOut),
+        Outcome::Ok,
+        Outcome::Ok,
+        Outcome::Ok,
+    ])
+    .with_read_script(vec![
+        (
+            Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+            vec![],
+        ),
+        (Poll::Ready(Ok(())), b"x".to_vec()),
+    ]);
+    let asked: Arc<Mutex<Vec<(ErrorKind, usize)>>> = Arc::default();
+    let asked_cb = asked.clone();
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(3))
+        .with_retry_policy(move |error: &io::Error, attempt| {
+            asked_cb.lock().unwrap().push((error.kind(), attempt));
+            match error.kind() {
+                ErrorKind::ConnectionRefused => RetryDecision::Retry(Duration::from_millis(7)),
+                ErrorKind::ConnectionReset => RetryDecision::Retry(Duration::from_millis(9)),
+                _ => RetryDecision::UseDefault,
+            }
+        })
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    s.read_exact(&mut [0u8; 1]).await.unwrap();
+    s.force_reconnect();
+    s.write_all(b"x").await.unwrap();
+
+    assert_eq!(
+        *asked.lock().unwrap(),
+        vec![
+            (ErrorKind::ConnectionRefused, 1),
+            (ErrorKind::TimedOut, 2),
+            (ErrorKind::ConnectionReset, 1),
+        ]
+    );
+    assert_eq!(
+        scheduled_delays(&log),
+        vec![
+            "ReconnectScheduled { attempt: 1, delay: 7ms }",
+            "ReconnectScheduled { attempt: 2, delay: 5ms }",
+            "ReconnectScheduled { attempt: 1, delay: 9ms }",
+            "ReconnectScheduled { attempt: 1, delay: 5ms }",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn retry_policy_give_up_is_terminal() {
+    // `InvalidData` is not fatal for `DummyIo`, so only the policy can end the
+    // sequence before the retries run out.
+    let ctor = DummyCtor::new(vec![Outcome::Err(ErrorKind::InvalidData)]);
+    let asked = Arc::new(AtomicUsize::new(0));
+    let asked_cb = asked.clone();
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(3))
+        .with_retry_policy(move |error: &io::Error, _| {
+            asked_cb.fetch_add(1, Ordering::Relaxed);
+            if error.kind() == ErrorKind::InvalidData {
+                RetryDecision::GiveUp
+            } else {
+                RetryDecision::UseDefault
+            }
+        })
+        .with_event_callback(cb);
+
+    let err = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(asked.load(Ordering::Relaxed), 1);
+    assert_eq!(ctor.establish_count(), 1);
+    let log = log.lock().unwrap();
+    assert!(log[0].starts_with("ConnectFailed"), "{log:?}");
+    assert_eq!(log[1..], ["Exhausted { reason: RetryPolicy }"]);
+}
+
+// ---------------------------------------------------------------------------
+// Maximum disconnected duration
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn max_disconnected_duration_gives_up_with_retries_left() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]).with_read_script(vec![(
+        Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+        vec![],
+    )]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(10); 1000])
+        .with_max_disconnected_duration(Duration::from_millis(60))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    let err = s.read_exact(&mut [0u8; 1]).await.unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(s.is_terminated());
+    assert_eq!(
+        log.lock().unwrap().last().unwrap(),
+        "Exhausted { reason: MaxDisconnectedDuration }"
+    );
+}
+
+#[tokio::test]
+async fn max_disconnected_duration_abandons_a_pending_initial_attempt() {
+    let ctor = DummyCtor::new(vec![Outcome::SlowOk(Duration::from_secs(60))]);
+    let opts = ReconnectOptions::new().with_max_disconnected_duration(Duration::from_millis(20));
+
+    let err = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert_eq!(ctor.establish_count(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// State watcher
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn state_watcher_follows_reconnect_and_shutdown() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(1));
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    let rx = s.state_watcher();
+    assert_eq!(*rx.borrow(), ConnectionState::Connected);
+
+    s.force_reconnect();
+    assert!(matches!(
+        *rx.borrow(),
+        ConnectionState::Reconnecting { attempt: 1, .. }
+    ));
+    s.write_all(b"x").await.unwrap();
+    assert_eq!(*rx.borrow(), ConnectionState::Connected);
+    s.shutdown().await.unwrap();
+    assert_eq!(*rx.borrow(), ConnectionState::Closed);
+}
+
+#[tokio::test]
+async fn unrepresentable_delay_saturates_instead_of_panicking() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::MAX])
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let rx = s.state_watcher();
+
+    s.force_reconnect();
+    let write = tokio::time::timeout(Duration::from_millis(20), s.write_all(b"x")).await;
+
+    assert!(write.is_err(), "the attempt is still waiting");
+    assert_eq!(ctor.establish_count(), 1);
+    assert_eq!(
+        log.lock().unwrap().last().unwrap(),
+        &format!(
+            "ReconnectScheduled {{ attempt: 1, delay: {:?} }}",
+            Duration::MAX
+        )
+    );
+    let ConnectionState::Reconnecting { attempt, next_at } = *rx.borrow() else {
+        panic!("expected Reconnecting");
+    };
+    assert_eq!(attempt, 1);
+    assert!(next_at > tokio::time::Instant::now() + Duration::from_secs(86400 * 365));
+}
+
+#[tokio::test]
+async fn state_watcher_wakes_waiters_on_exhaustion() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]).with_read_script(vec![(
+        Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+        vec![],
+    )]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(1));
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    let mut rx = s.state_watcher();
+    let waiter = tokio::spawn(async move {
+        rx.wait_for(|state| *state == ConnectionState::Exhausted)
+            .await
+            .is_ok()
+    });
+
+    let err = s.read(&mut [0u8; 1]).await.unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(waiter.await.unwrap());
+}
+
+// ---------------------------------------------------------------------------
+// Background reconnect
+// ---------------------------------------------------------------------------
+
+#[tokio::test(start_paused = true)]
+async fn background_reconnect_connects_while_nobody_polls() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
+        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
+    )]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_background_reconnect::<DummyIo>(true)
+        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    s.write_all(b"lost").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    assert_eq!(ctor.establish_count(), 2);
+    assert_eq!(
+        log.lock().unwrap().last().unwrap(),
+        "Connected { attempt: 1 }"
+    );
+    s.write_all(b"next").await.unwrap();
+    assert_eq!(ctor.written(), vec![Vec::new(), b"next".to_vec()]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn background_reconnect_starts_only_once_a_poll_sees_the_drop() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![(
+        Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+        vec![],
+    )]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_background_reconnect::<DummyIo>(true)
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(ctor.establish_count(), 1);
+    assert!(s.is_connected());
+
+    let read = tokio::time::timeout(Duration::from_millis(50), s.read(&mut [0u8; 1])).await;
+    assert!(read.is_err(), "the new connection has nothing to read");
+    assert_eq!(ctor.establish_count(), 2);
+    assert!(
+        log.lock()
+            .unwrap()
+            .iter()
+            .any(|e| e.starts_with("Disconnected"))
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_and_drop_abort_background_reconnect() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok, Outcome::Ok, Outcome::Ok]);
+    let opts = || {
+        ReconnectOptions::new()
+            .with_background_reconnect::<DummyIo>(true)
+            .with_retries_generator(|| vec![Duration::from_millis(20)])
+    };
+    let mut shut = StubbornDummy::connect_with_options(ctor.clone(), opts())
+        .await
+        .unwrap();
+    let mut dropped = StubbornDummy::connect_with_options(ctor.clone(), opts())
+        .await
+        .unwrap();
+
+    shut.force_reconnect();
+    let _ = shut.shutdown().await;
+    dropped.force_reconnect();
+    drop(dropped);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(shut.is_closed());
+    assert_eq!(ctor.establish_count(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// Event listeners
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn listeners_receive_every_event_until_unregistered() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let log: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let listener = |name: &'static str| {
+        let log = log.clone();
+        move |ev: ReconnectEvent<'_>| log.lock().unwrap().push(format!("{name}: {ev:?}"))
+    };
+    let opts = ReconnectOptions::new().with_event_callback(listener("callback"));
+    let first = opts.add_event_listener(listener("first"));
+    let _second = opts.add_event_listener(listener("second"));
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    first.unregister();
+    s.set_context(ctor);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "callback: Connected { attempt: 0 }",
+            "first: Connected { attempt: 0 }",
+            "second: Connected { attempt: 0 }",
+            "callback: ContextChanged",
+            "second: ContextChanged",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn panicking_listener_does_not_starve_the_others() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new().with_event_channel(4);
+    opts.add_event_listener(|_| panic!("listener bug"));
+    opts.add_event_listener(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let handle = s.add_event_listener(|_| panic!("late listener bug"));
+    s.set_context(ctor);
+    handle.unregister();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["Connected { attempt: 0 }", "ContextChanged"]
+    );
+    assert!(s.is_connected());
+    let events = s.take_event_stream().unwrap();
+    drop(s);
+    assert_eq!(events.count().await, 2);
+}
+
+// ---------------------------------------------------------------------------
+// Event stream
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn event_stream_delivers_owned_events_and_ends_on_drop() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Ok,
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(2))
+        .with_connection_name("feed")
+        .with_event_channel(16);
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    let events = s.take_event_stream().unwrap();
+    assert!(s.take_event_stream().is_none());
+    drop(s);
+
+    assert_eq!(events.lagged(), 0);
+    let events: Vec<OwnedReconnectEvent> = events.collect().await;
+    assert!(events.iter().all(|ev| &*ev.connection_name == "feed"));
+    let kinds: Vec<&OwnedEventKind> = events.iter().map(|ev| &ev.kind).collect();
+    assert!(matches!(
+        kinds[..],
+        [
+            OwnedEventKind::ConnectFailed { error, attempt: 0 },
+            OwnedEventKind::ReconnectScheduled { attempt: 1, .. },
+            OwnedEventKind::Connected { attempt: 1 },
+        ] if error.kind() == ErrorKind::ConnectionRefused
+    ));
+}
+
+#[tokio::test]
+async fn event_stream_drops_oldest_when_full() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_event_callback(cb)
+        .with_event_channel(2);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let mut events = s.take_event_stream().unwrap();
+    for _ in 0..3 {
+        s.set_context(ctor.clone());
+    }
+
+    // Nobody read the stream, yet emitting never blocked and the callback
+    // saw everything.
+    assert_eq!(log.lock().unwrap().len(), 4);
+    assert_eq!(events.lagged(), 2);
+    for _ in 0..2 {
+        let ev = events.next().await.unwrap();
+        assert!(matches!(ev.kind, OwnedEventKind::ContextChanged));
+    }
+    assert!(futures::poll!(events.next()).is_pending());
+}
+
+// ---------------------------------------------------------------------------
+// Statistics
+// ---------------------------------------------------------------------------
+
+#[tokio::test(start_paused = true)]
+async fn stats_track_traffic_reconnects_and_errors() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Ok,
+        Outcome::Ok,
+    ])
+    .with_read_script(vec![(Poll::Ready(Ok(())), b"abc".to_vec())])
+    .with_write_script(vec![
+        None,
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::BrokenPipe,
+            "peer gone",
+        )))),
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_background_reconnect::<DummyIo>(true)
+        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
+        .with_retries_generator(fast_retries(3));
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    let after_connect = s.stats();
+    assert_eq!(after_connect.failed_attempts, 1);
+    assert_eq!(after_connect.last_error, Some(ErrorKind::ConnectionRefused));
+    assert!(after_connect.uptime.is_some());
+
+    s.read_exact(&mut [0u8; 3]).await.unwrap();
+    s.write_all(b"hello").await.unwrap();
+    s.write_all(b"lost").await.unwrap();
+    let disconnected = s.stats();
+    assert_eq!(disconnected.disconnects, 1);
+    assert_eq!(disconnected.uptime, None);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    s.write_all(b"next").await.unwrap();
+
+    let stats = s.stats();
+    assert_eq!(stats.reconnects, 1);
+    assert_eq!(stats.failed_attempts, 1);
+    assert_eq!(stats.bytes_read, 3);
+    assert_eq!(stats.bytes_written, 9);
+    assert_eq!(stats.bytes_dropped, 4);
+    assert_eq!(stats.last_error, Some(ErrorKind::BrokenPipe));
+    assert!(stats.uptime.is_some());
+    assert!(stats.last_connected.is_some());
+    assert!(stats.total_downtime > Duration::ZERO);
+    assert_eq!(stats.outages.count, 1);
+    assert_eq!(stats.outages.buckets[0], (Duration::from_millis(100), 1));
+}
+
+#[tokio::test]
+async fn stats_handle_outlives_the_stream_and_stops_at_shutdown() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let mut s = StubbornDummy::connect(ctor).await.unwrap();
+    let handle = s.stats_handle();
+
+    s.shutdown().await.unwrap();
+    drop(s);
+    let closed = handle.snapshot();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    assert_eq!(closed.uptime, None);
+    assert_eq!(handle.snapshot(), closed);
+    assert_eq!(closed.outages.count, 0);
+}
+
+// ---------------------------------------------------------------------------
+// Split halves
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn read_half_disconnect_reconnects_once_for_both_halves() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![
+        (
+            Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+            vec![],
+        ),
+        (Poll::Ready(Ok(())), b"x".to_vec()),
+    ]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(2));
+    let s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let (mut rd, mut wr) = s.into_split();
+
+    let mut buf = [0u8; 1];
+    rd.read_exact(&mut buf).await.unwrap();
+    wr.write_all(b"y").await.unwrap();
+
+    assert_eq!(&buf, b"x");
+    assert_eq!(ctor.establish_count(), 2);
+    assert_eq!(ctor.written(), vec![Vec::new(), b"y".to_vec()]);
+}
+
+#[tokio::test]
+async fn write_half_reconnect_wakes_parked_read_half() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
+        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
+    )]);
+    let opts = ReconnectOptions::new().with_retries_generator(fast_retries(2));
+    let s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    let (mut rd, mut wr) = s.into_split();
+
+    // The dummy never wakes a pending read itself, so the reader only makes
+    // progress if the write half's disconnect and reconnect wake it.
+    let reader = tokio::spawn(async move {
+        let mut buf = [0u8; 2];
+        rd.read_exact(&mut buf).await.map(|_| buf)
+    });
+    tokio::task::yield_now().await;
+    ctor.read_script
+        .lock()
+        .unwrap()
+        .push((Poll::Ready(Ok(())), b"hi".to_vec()));
+    wr.write_all(b"y").await.unwrap();
+
+    let got = tokio::time::timeout(Duration::from_millis(200), reader)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(&got, b"hi");
+    assert_eq!(ctor.establish_count(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// Post-connect hook
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn on_established_runs_before_connected_on_every_connect() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![(
+        Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+        Vec::new(),
+    )]);
+    let (log, cb) = event_sink();
+    let hook_log = log.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(cb)
+        .with_on_established(move |io: &mut DummyIo| {
+            hook_log.lock().unwrap().push("hook".to_string());
+            Box::pin(async move { io.write_all(b"LOGIN\n").await })
+        });
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    // Trigger a reconnect; the read itself then parks on the empty script.
+    let _ = tokio::time::timeout(Duration::from_millis(100), s.read(&mut [0u8; 8])).await;
+    s.write_all(b"data").await.unwrap();
+
+    assert_eq!(
+        ctor.written(),
+        vec![b"LOGIN\n".to_vec(), b"LOGIN\ndata".to_vec()]
+    );
+    let log = log.lock().unwrap();
+    let sequence: Vec<&str> = log
+        .iter()
+        .map(String::as_str)
+        .filter(|e| *e == "hook" || e.starts_with("Connected"))
+        .collect();
+    assert_eq!(
+        sequence,
+        [
+            "hook",
+            "Connected { attempt: 0 }",
+            "hook",
+            "Connected { attempt: 1 }"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn on_established_failure_counts_as_failed_attempt() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]);
+    let (log, cb) = event_sink();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook_calls = calls.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(cb)
+        .with_on_established(move |_: &mut DummyIo| {
+            let first = hook_calls.fetch_add(1, Ordering::Relaxed) == 0;
+            Box::pin(async move {
+                if first {
+                    Err(io::Error::new(
+                        ErrorKind::ConnectionReset,
+                        "banner rejected",
+                    ))
+                } else {
+                    Ok(())
+                }
+            })
+        });
+    let s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    assert!(s.is_connected());
+    assert_eq!(ctor.establish_count(), 2);
+    let log = log.lock().unwrap();
+    assert!(log[0].starts_with("ConnectFailed") && log[0].contains("banner rejected"));
+    assert_eq!(log[1], "ReconnectScheduled { attempt: 1, delay: 5ms }");
+    assert_eq!(log[2], "Connected { attempt: 1 }");
+}
+
+// ---------------------------------------------------------------------------
+// Heartbeat
+// ---------------------------------------------------------------------------
+
+fn heartbeat_opts() -> ReconnectOptions {
+    ReconnectOptions::new()
+        .with_heartbeat(Duration::from_millis(20), b"HB\n")
+        .with_retries_generator(fast_retries(2))
+}
+
+#[tokio::test]
+async fn heartbeat_is_sent_after_write_inactivity() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), heartbeat_opts())
+        .await
+        .unwrap();
+    let ahead = Duration::from_millis(200);
+
+    tokio::time::timeout(ahead, s.heartbeat())
+        .await
+        .unwrap()
+        .unwrap();
+    s.write_all(b"data").await.unwrap();
+    tokio::time::timeout(ahead, s.heartbeat())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ctor.written(), vec![b"HB\ndataHB\n".to_vec()]);
+}
+
+#[tokio::test]
+async fn heartbeat_waits_for_short_caller_write_to_complete() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]).with_write_script(vec![Some(Poll::Ready(Ok(2)))]);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), heartbeat_opts())
+        .await
+        .unwrap();
+
+    assert_eq!(s.write(b"abcd").await.unwrap(), 2);
+    // Well past the interval, but the caller is mid-message.
+    let deferred = tokio::time::timeout(Duration::from_millis(60), s.heartbeat()).await;
+    assert!(deferred.is_err());
+    s.write_all(b"cd").await.unwrap();
+    tokio::time::timeout(Duration::from_millis(200), s.heartbeat())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ctor.written(), vec![b"abcdHB\n".to_vec()]);
+}
+
+#[tokio::test]
+async fn write_half_drives_heartbeat() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let s = StubbornDummy::connect_with_options(ctor.clone(), heartbeat_opts())
+        .await
+        .unwrap();
+    let (_rd, mut wr) = s.into_split();
+
+    wr.write_all(b"data").await.unwrap();
+    tokio::time::timeout(Duration::from_millis(200), wr.heartbeat())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ctor.written(), vec![b"dataHB\n".to_vec()]);
+}
+
+#[tokio::test]
+async fn unrepresentable_heartbeat_interval_never_fires() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok]);
+    let opts = ReconnectOptions::new().with_heartbeat(Duration::MAX, b"HB\n");
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+
+    s.write_all(b"data").await.unwrap();
+    let idle = tokio::time::timeout(Duration::from_millis(20), s.heartbeat()).await;
+
+    assert!(idle.is_err());
+    assert_eq!(ctor.written(), vec![b"data".to_vec()]);
+}
+
+#[tokio::test]
+async fn heartbeat_write_failure_reconnects() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
+        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
+    )]);
+    let (log, cb) = event_sink();
+    let mut s =
+        StubbornDummy::connect_with_options(ctor.clone(), heartbeat_opts().with_event_callback(cb))
+            .await
+            .unwrap();
+
+    tokio::time::timeout(Duration::from_millis(200), s.heartbeat())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ctor.written(), vec![Vec::new(), b"HB\n".to_vec()]);
+    let log = log.lock().unwrap();
+    assert!(
+        log.iter()
+            .any(|e| e == "Disconnected { reason: IoError(BrokenPipe) }"),
+        "{log:?}"
+    );
+    assert!(
+        log.iter().any(|e| e == "Connected { attempt: 1 }"),
+        "{log:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Write failure policy
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn drop_and_notify_returns_buf_len_and_emits_event() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
+        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
+    )]);
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let dc = dropped.clone();
+    let opts = ReconnectOptions::new()
+        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(move |ev| {
+            if let ReconnectEvent::WriteWhileDisconnected { bytes_dropped } = ev {
+                dc.fetch_add(bytes_dropped, Ordering::Relaxed);
+            }
+        });
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    let n = s.write(b"hello").await.unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(dropped.load(Ordering::Relaxed), 5);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drop_and_notify_write_that_sees_exhaustion_fails() {
+    let ctor = DummyCtor::new(vec![
+        Outcome::Ok,
+        Outcome::Err(ErrorKind::ConnectionRefused),
+    ])
+    .with_write_script(vec![Some(Poll::Ready(Err(io::Error::new(
+        ErrorKind::BrokenPipe,
+        "peer gone",
+    ))))]);
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let dc = dropped.clone();
+    let opts = ReconnectOptions::new()
+        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
+        .with_retries_generator(fast_retries(1))
+        .with_event_callback(move |ev| {
+            if let ReconnectEvent::WriteWhileDisconnected { bytes_dropped } = ev {
+                dc.fetch_add(bytes_dropped, Ordering::Relaxed);
+            }
+        });
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    assert_eq!(s.write(b"hello").await.unwrap(), 5);
+    // The write that finds the only retry failed must report it rather than
+    // claim the bytes were accepted.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let err = s.write(b"world").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(s.is_terminated());
+    assert_eq!(dropped.load(Ordering::Relaxed), 5);
+}
+
+#[tokio::test]
+async fn drop_and_notify_vectored_returns_sum_of_lengths() {
+    // Connected-path vectored write that reveals a disconnect via the underlying
+    // poll. The crate-level `poll_write_vectored` lives over `StubbornIo`, but
+    // tokio's default impl falls back to `poll_write` for non-vectored sinks,
+    // so we exercise the same DropAndNotify accounting via a sequence of writes.
+    let bufs = [b"hello".as_slice(), b" ".as_slice(), b"world".as_slice()];
+    let total: usize = bufs.iter().map(|b| b.len()).sum();
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![Some(
+        Poll::Ready(Err(io::Error::new(ErrorKind::BrokenPipe, "peer gone"))),
+    )]);
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let dc = dropped.clone();
+    let opts = ReconnectOptions::new()
+        .with_write_failure_policy(WriteFailurePolicy::DropAndNotify)
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(move |ev| {
+            if let ReconnectEvent::WriteWhileDisconnected { bytes_dropped } = ev {
+                dc.fetch_add(bytes_dropped, Ordering::Relaxed);
+            }
+        });
+    let mut s = StubbornDummy::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    // Use the vectored entry point directly via the AsyncWriteExt::write_vectored.
+    let slices: Vec<IoSlice<'_>> = bufs.iter().map(|b| IoSlice::new(b)).collect();
+    let n = s.write_vectored(&slices).await.unwrap();
+    assert_eq!(n, total);
+    assert_eq!(dropped.load(Ordering::Relaxed), total);
+}
+
+#[tokio::test]
+async fn replay_resends_retained_writes_after_reconnect() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![
+        None,
+        None,
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::BrokenPipe,
+            "peer gone",
+        )))),
+    ]);
+    let (log, cb) = event_sink();
+    let opts = ReconnectOptions::new()
+        .with_write_failure_policy(WriteFailurePolicy::Replay { capacity: 8 })
+        .with_retries_generator(fast_retries(2))
+        .with_event_callback(cb);
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    s.write_all(b"first").await.unwrap();
+    s.write_all(b"abc").await.unwrap();
+    // Held under back-pressure, then written after the replay.
+    s.write_all(b"xyz").await.unwrap();
+
+    // The write before the disconnect fits the history bound alongside "first".
+    assert_eq!(
+        ctor.written(),
+        vec![b"firstabc".to_vec(), b"firstabcxyz".to_vec()]
+    );
+    let log = log.lock().unwrap();
+    let connected = log.iter().position(|e| e == "Connected { attempt: 1 }");
+    let replayed = log.iter().position(|e| e == "Replayed { bytes: 8 }");
+    assert!(connected.is_some() && connected < replayed, "{log:?}");
+}
+
+#[tokio::test]
+async fn replay_evicts_oldest_writes_beyond_capacity() {
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_write_script(vec![
+        None,
+        None,
+        Some(Poll::Ready(Err(io::Error::new(
+            ErrorKind::ConnectionReset,
+            "peer gone",
+        )))),
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_write_failure_policy(WriteFailurePolicy::Replay { capacity: 4 })
+        .with_retries_generator(fast_retries(2));
+    let mut s = StubbornDummy::connect_with_options(ctor.clone(), opts)
+        .await
+        .unwrap();
+    s.write_all(b"old").await.unwrap();
+    s.write_all(b"new").await.unwrap();
+    s.write_all(b"!").await.unwrap();
+
+    assert_eq!(ctor.written(), vec![b"oldnew".to_vec(), b"new!".to_vec()]);
+}
+
+// ---------------------------------------------------------------------------
+// Disconnect-kind defaults
+// ---------------------------------------------------------------------------
+
+#[test]
+fn default_is_disconnect_error_covers_canonical_set() {
+    let io_dummy = DummyIo::new(&DummyCtor::default());
+    let disconnect_kinds = [
+        ErrorKind::ConnectionRefused,
+        ErrorKind::ConnectionReset,
+        ErrorKind::ConnectionAborted,
+        ErrorKind::NotConnected,
+        ErrorKind::AddrInUse,
+        ErrorKind::AddrNotAvailable,
+        ErrorKind::BrokenPipe,
+        ErrorKind::TimedOut,
+        ErrorKind::UnexpectedEof,
+        ErrorKind::HostUnreachable,
+        ErrorKind::NetworkUnreachable,
+        ErrorKind::NetworkDown,
+    ];
+    for kind in disconnect_kinds {
+        let err = io::Error::new(kind, "x");
+        assert!(io_dummy.is_disconnect_error(&err), "should match: {kind:?}");
+    }
+    let benign = [
+        ErrorKind::WouldBlock,
+        ErrorKind::Interrupted,
+        ErrorKind::Other,
+        ErrorKind::InvalidData,
+    ];
+    for kind in benign {
+        let err = io::Error::new(kind, "x");
+        assert!(
+            !io_dummy.is_disconnect_error(&err),
+            "should NOT match: {kind:?}"
+        );
+    }
+}
+
+#[test]
+fn tcp_overrides_drop_unexpected_eof() {
+    use tokio::net::TcpStream;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let tcp = rt.block_on(async move { TcpStream::connect(addr).await.unwrap() });
+    let err = io::Error::new(ErrorKind::UnexpectedEof, "x");
+    assert!(!tcp.is_disconnect_error(&err));
     let err = io::Error::new(ErrorKind::ConnectionReset, "x");
     assert!(tcp.is_disconnect_error(&err));
 }
+
+#[cfg(unix)]
+#[test]
+fn unix_treats_missing_socket_as_disconnect() {
+    use tokio::net::UnixStream;
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let (unix, _peer) = rt.block_on(async { UnixStream::pair().unwrap() });
+    for kind in [ErrorKind::NotFound, ErrorKind::ConnectionRefused] {
+        let err = io::Error::new(kind, "x");
+        assert!(unix.is_disconnect_error(&err), "should match: {kind:?}");
+    }
+    let err = io::Error::new(ErrorKind::NetworkUnreachable, "x");
+    assert!(!unix.is_disconnect_error(&err));
+}
@@ -0,0 +1,211 @@
+//! Real-socket tests for `StubbornTlsStream` against a local rustls server with a
+//! freshly generated self-signed certificate. Only built with `--features tls`.
+#![cfg(feature = "tls")]
+#![allow(
+    missing_docs,
+    clippy::missing_panics_doc,
+    clippy::significant_drop_tightening
+)]
+
+use sdre_stubborn_io::ReconnectOptions;
+use sdre_stubborn_io::config::ReconnectEvent;
+use sdre_stubborn_io::tokio::rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer, ServerName};
+use sdre_stubborn_io::tokio::rustls::{
+    ClientConfig, Error as TlsError, RootCertStore, ServerConfig,
+};
+use sdre_stubborn_io::tokio::{HostPort, StubbornTlsStream, TlsContext};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+struct TestPki {
+    server: Arc<ServerConfig>,
+    trusting_client: Arc<ClientConfig>,
+}
+
+fn pki() -> TestPki {
+    let key = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let cert_der = key.cert.der().clone();
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.signing_key.serialize_der()));
+
+    let server = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der.clone()], key_der)
+        .unwrap();
+    let mut roots = RootCertStore::empty();
+    roots.add(cert_der).unwrap();
+    let trusting_client = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TestPki {
+        server: Arc::new(server),
+        trusting_client: Arc::new(trusting_client),
+    }
+}
+
+fn localhost() -> ServerName<'static> {
+    ServerName::try_from("localhost").unwrap()
+}
+
+/// TLS echo server. The first `hang_ups` TCP connections are dropped before the
+/// handshake; every later connection echoes a single message then closes.
+async fn spawn_echo_server(config: Arc<ServerConfig>, hang_ups: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let acceptor = TlsAcceptor::from(config);
+    tokio::spawn(async move {
+        let mut remaining_hang_ups = hang_ups;
+        loop {
+            let (tcp, _) = listener.accept().await.unwrap();
+            if remaining_hang_ups > 0 {
+                remaining_hang_ups -= 1;
+                drop(tcp);
+                continue;
+            }
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut tls) = acceptor.accept(tcp).await else {
+                    return;
+                };
+                let mut buf = [0u8; 4];
+                if tls.read_exact(&mut buf).await.is_ok() {
+                    let _ = tls.write_all(&buf).await;
+                    let _ = tls.shutdown().await;
+                }
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn tls_round_trip_and_reconnect() {
+    let pki = pki();
+    let addr = spawn_echo_server(pki.server.clone(), 0).await;
+    let reconnects = Arc::new(AtomicUsize::new(0));
+    let rc = reconnects.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(5); 5])
+        .with_event_callback(move |ev| {
+            if matches!(ev, ReconnectEvent::Connected { attempt } if attempt > 0) {
+                rc.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    let ctx = TlsContext::new(addr, localhost(), pki.trusting_client.clone());
+    let mut stream = StubbornTlsStream::connect_with_options(ctx, opts)
+        .await
+        .unwrap();
+
+    for round in 0..2 {
+        stream.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buf, b"ping");
+
+        // The server closes after each echo. The next read observes the
+        // close_notify, reconnects with a fresh handshake, then idles.
+        let idle = tokio::time::timeout(Duration::from_millis(200), stream.read(&mut buf)).await;
+        assert!(idle.is_err());
+        assert_eq!(reconnects.load(Ordering::Relaxed), round + 1);
+    }
+}
+
+#[tokio::test]
+async fn tls_reports_resolved_addr_only_for_host_endpoints() {
+    let pki = pki();
+    let addr = spawn_echo_server(pki.server.clone(), 0).await;
+    let contexts = [
+        (
+            TlsContext::new(addr, localhost(), pki.trusting_client.clone()),
+            None,
+        ),
+        (
+            TlsContext::with_host(
+                HostPort::new("127.0.0.1", addr.port()),
+                localhost(),
+                pki.trusting_client.clone(),
+            ),
+            Some(addr),
+        ),
+    ];
+    for (ctx, expected) in contexts {
+        let resolved = Arc::new(Mutex::new(None));
+        let r = resolved.clone();
+        let opts = ReconnectOptions::new().with_event_callback(move |ev| {
+            if let ReconnectEvent::AddressResolved { addr, .. } = ev {
+                *r.lock().unwrap() = Some(addr);
+            }
+        });
+        let stream = StubbornTlsStream::connect_with_options(ctx, opts)
+            .await
+            .unwrap();
+        assert!(stream.is_connected());
+        assert_eq!(*resolved.lock().unwrap(), expected);
+    }
+}
+
+#[tokio::test]
+async fn tls_handshake_interrupted_is_retried() {
+    let pki = pki();
+    let addr = spawn_echo_server(pki.server.clone(), 2).await;
+    let failures = Arc::new(AtomicUsize::new(0));
+    let f = failures.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(5); 5])
+        .with_event_callback(move |ev| {
+            if matches!(ev, ReconnectEvent::ConnectFailed { .. }) {
+                f.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    let ctx = TlsContext::new(addr, localhost(), pki.trusting_client.clone());
+    let stream = StubbornTlsStream::connect_with_options(ctx, opts)
+        .await
+        .unwrap();
+    assert!(stream.is_connected());
+    assert_eq!(failures.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn tls_untrusted_certificate_is_fatal() {
+    let server_pki = pki();
+    let addr = spawn_echo_server(server_pki.server.clone(), 0).await;
+    // Trusts a different, unrelated self-signed certificate.
+    let untrusting_client = pki().trusting_client;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let log = events.clone();
+    let opts = ReconnectOptions::new()
+        .with_retries_generator(|| vec![Duration::from_millis(5); 5])
+        .with_event_callback(move |ev| log.lock().unwrap().push(format!("{ev:?}")));
+    let ctx = TlsContext::new(addr, localhost(), untrusting_client);
+    let err = StubbornTlsStream::connect_with_options(ctx, opts)
+        .await
+        .err()
+        .expect("expected handshake with untrusted certificate to fail");
+
+    let tls_err = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<TlsError>())
+        .expect("expected a rustls error");
+    assert!(matches!(tls_err, TlsError::InvalidCertificate(_)));
+    let events = events.lock().unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| e.starts_with("ConnectFailed"))
+            .count(),
+        1
+    );
+    assert!(!events.iter().any(|e| e.starts_with("ReconnectScheduled")));
+    assert_eq!(
+        events.last().map(String::as_str),
+        Some("Exhausted { reason: FatalError }")
+    );
+}
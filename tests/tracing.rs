@@ -0,0 +1,111 @@
+//! Spans recorded with the `tracing` feature, captured through a
+//! `tracing-subscriber` fmt layer. Only built with `--features tracing`.
+#![cfg(feature = "tracing")]
+#![allow(missing_docs, clippy::missing_panics_doc)]
+
+mod common;
+
+use common::{DummyCtor, DummyIo, Outcome};
+use sdre_stubborn_io::ReconnectOptions;
+use sdre_stubborn_io::tokio::StubbornIo;
+use std::io::{self, ErrorKind, Write};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Collects everything the fmt subscriber writes.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture() -> (Captured, tracing::subscriber::DefaultGuard) {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let guard = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .without_time()
+        .with_target(false)
+        .finish()
+        .set_default();
+    (captured, guard)
+}
+
+#[tokio::test]
+async fn attempts_get_child_spans_with_structured_error_kind() {
+    let (captured, _guard) = capture();
+    let ctor = DummyCtor::new(vec![
+        Outcome::Err(ErrorKind::ConnectionRefused),
+        Outcome::Ok,
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_connection_name("feed")
+        .with_retries_generator(|| vec![Duration::from_millis(5)]);
+    let s = StubbornIo::<DummyIo>::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+    drop(s);
+
+    let lines = captured.lines();
+    let closed_attempts: Vec<&String> = lines
+        .iter()
+        .filter(|l| l.contains("connect_attempt{") && l.ends_with("close"))
+        .collect();
+    assert_eq!(closed_attempts.len(), 2, "{lines:#?}");
+    assert!(closed_attempts[0].contains("stubborn_io{connection_name=feed}"));
+    assert!(closed_attempts[0].contains("attempt=0 delay=0ns error.kind=ConnectionRefused"));
+    assert!(closed_attempts[1].contains("attempt=1 delay=5ms}"));
+}
+
+#[tokio::test]
+async fn disconnects_are_recorded_in_the_stream_span() {
+    let (captured, _guard) = capture();
+    let ctor = DummyCtor::new(vec![Outcome::Ok, Outcome::Ok]).with_read_script(vec![
+        (
+            Poll::Ready(Err(io::Error::new(ErrorKind::ConnectionReset, "reset"))),
+            vec![],
+        ),
+        (Poll::Ready(Ok(())), b"x".to_vec()),
+    ]);
+    let opts = ReconnectOptions::new()
+        .with_connection_name("feed")
+        .with_retries_generator(|| vec![Duration::from_millis(5)]);
+    let mut s = StubbornIo::<DummyIo>::connect_with_options(ctor, opts)
+        .await
+        .unwrap();
+
+    s.read_exact(&mut [0u8; 1]).await.unwrap();
+
+    let lines = captured.lines();
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.starts_with("ERROR stubborn_io{connection_name=feed}")
+                && l.ends_with("Disconnect occurred error.kind=ConnectionReset")),
+        "{lines:#?}"
+    );
+}